@@ -8,15 +8,15 @@
 
 use std::fs;
 use std::path::Path;
-#[cfg(not(target_os = "windows"))]
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
 use std::process::Command;
 use std::time::Duration;
 use tauri::{AppHandle, Manager};
 
 // 导入项目内部模块
-use crate::data::load_app_settings;
+use crate::data::get_default_settings;
 use crate::helpers::{extract_file_icon, resolve_shortcut_target};
-use crate::models::AppSettings;
+use crate::models::AppState;
 
 fn collect_shortcuts(dir: &Path, shortcuts: &mut Vec<String>) {
     let Ok(entries) = fs::read_dir(dir) else {
@@ -80,6 +80,10 @@ pub fn list_start_menu_items() -> Result<Vec<serde_json::Value>, String> {
         })
         .collect();
 
+    // Linux 上列出 XDG 应用目录中的 .desktop 文件
+    #[cfg(target_os = "linux")]
+    items.extend(crate::desktop_entry::list_applications());
+
     items.sort_by(|a, b| {
         a.get("name")
             .and_then(|value| value.as_str())
@@ -215,8 +219,20 @@ pub fn get_shell_file_icon(file_path: String) -> Result<String, String> {
         icon_result
     }
 
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(target_os = "linux")]
+    {
+        // .desktop 文件使用其中 Icon 指定的图标
+        let path = Path::new(&file_path);
+        if !crate::desktop_entry::is_desktop_entry_path(path) {
+            return Err("当前平台不支持 Shell 图标".to_string());
+        }
+        let entry = crate::desktop_entry::DesktopEntry::load(path)?;
+        crate::desktop_entry::icon_data_url(&entry).ok_or_else(|| "未找到桌面项的图标".to_string())
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
     {
+        let _ = file_path;
         Err("当前平台不支持 Shell 图标".to_string())
     }
 }
@@ -300,7 +316,10 @@ pub fn get_file_info(file_path: String) -> Result<serde_json::Value, String> {
         .unwrap()
         .as_millis() as i64;
 
-    Ok(serde_json::json!({
+    #[cfg(target_os = "linux")]
+    let is_desktop_entry = extension == "desktop";
+
+    let info = serde_json::json!({
         "id": id,
         "name": name,
         "path": file_path,  // 始终返回原始路径（快捷方式路径）
@@ -310,7 +329,17 @@ pub fn get_file_info(file_path: String) -> Result<serde_json::Value, String> {
         "is_shortcut": is_shortcut,
         "target_path": target_path,  // 快捷方式的目标路径
         "icon": icon_base64
-    }))
+    });
+
+    // .desktop 文件使用其中的名称、启动参数和目标类型
+    #[cfg(target_os = "linux")]
+    if is_desktop_entry {
+        if let Ok(entry) = crate::desktop_entry::DesktopEntry::load(path) {
+            return Ok(entry.apply_to_file_info(info));
+        }
+    }
+
+    Ok(info)
 }
 
 /// 启动应用程序
@@ -366,21 +395,39 @@ pub fn launch_app(
             .map_err(|e| format!("启动应用失败: {}", e))
     }
 
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(target_os = "linux")]
+    {
+        let args = crate::command_line::split_launch_args(launch_args.as_deref())?;
+        // .desktop 文件按其中的程序、工作目录和终端设置启动
+        let command = if crate::desktop_entry::is_desktop_entry_path(path) {
+            let entry = crate::desktop_entry::DesktopEntry::load(path)?;
+            if run_as_admin.unwrap_or(false) {
+                entry.elevated_command(&args)?
+            } else {
+                entry.command(&args)?
+            }
+        } else if run_as_admin.unwrap_or(false) {
+            crate::linux_native::elevated_command(path, &args)?
+        } else {
+            crate::linux_native::launch_command(path, &args)
+        };
+
+        crate::linux_native::spawn_detached(command)
+            .map(|_| "应用启动成功".to_string())
+            .map_err(|e| format!("启动应用失败: {}", e))
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
     {
+        let _ = run_as_admin;
         let mut cmd = Command::new(&app_path);
 
         // 重定向输出和错误，防止子进程崩溃影响父进程
         cmd.stdout(std::process::Stdio::null())
             .stderr(std::process::Stdio::null());
 
-        if let Some(args_str) = launch_args {
-            if !args_str.trim().is_empty() {
-                let split_args: Vec<String> =
-                    args_str.split_whitespace().map(|s| s.to_string()).collect();
-                cmd.args(&split_args);
-            }
-        }
+        let args = crate::command_line::split_launch_args(launch_args.as_deref())?;
+        cmd.args(&args);
 
         let result = cmd.spawn();
 
@@ -406,7 +453,11 @@ pub async fn launch_app_with_auto_hide(
     let launch_result = launch_app(app_path, launch_args, run_as_admin)?;
 
     // 检查是否启用了自动隐藏功能
-    let settings = load_app_settings().unwrap_or_else(|_| get_default_settings());
+    let settings = app
+        .state::<AppState>()
+        .store
+        .settings()
+        .unwrap_or_else(|_| get_default_settings());
     if settings.auto_hide_after_launch.unwrap_or(false) {
         // 延迟一段时间后隐藏窗口，让用户能看到应用已启动
         if let Some(window) = app.get_webview_window("main") {
@@ -438,53 +489,13 @@ pub fn get_app_icon(file_path: String) -> Result<String, String> {
 
         get_shell_file_icon(file_path)
     }
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(target_os = "linux")]
     {
-        Err("当前平台不支持图标提取".to_string())
+        get_shell_file_icon(file_path)
     }
-}
-
-/// 获取默认设置
-///
-/// 此函数返回应用程序的默认设置，用于在无法加载设置时提供后备值。
-fn get_default_settings() -> AppSettings {
-    AppSettings {
-        prevent_auto_hide: false,
-        window_width: Some(800),
-        window_height: Some(600),
-        settings_window_width: Some(800),
-        settings_window_height: Some(600),
-        new_project_window_width: Some(600),
-        new_project_window_height: Some(500),
-        window_layout: Some("horizontal".to_string()),
-        layout_locked: Some(false),
-        theme: Some("auto".to_string()),
-        icon_size: Some(88),
-        project_name_position: Some("bottom".to_string()),
-        sidebar_width: Some(0),
-        enable_animations: Some(true),
-        animation_speed: Some("normal".to_string()),
-        start_with_system: Some(false),
-        start_minimized: Some(false),
-        auto_hide_after_launch: Some(false),
-        toggle_hotkey: Some("Ctrl+Space".to_string()),
-        global_hotkey: Some(true),
-        middle_mouse_toggle: Some(false),
-        fuzzy_search: Some(true),
-        search_in_path: Some(false),
-        max_search_results: Some(20),
-        auto_backup: Some(true),
-        backup_interval: Some("weekly".to_string()),
-        last_backup_time: None,
-        next_backup_time: None,
-        // 界面状态记录默认值
-        active_tab: Some("about".to_string()),
-        last_selected_category: None,
-        window_position_x: None,
-        window_position_y: None,
-        last_search_query: None,
-        grid_view_enabled: Some(false),
-        sort_order: Some("manual".to_string()),
-        show_hidden_files: Some(false),
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        let _ = file_path;
+        Err("当前平台不支持图标提取".to_string())
     }
 }
@@ -1,11 +1,512 @@
-use crate::data::{get_app_data_dir, load_app_data, load_app_settings, save_app_settings};
-use chrono::{Datelike, Local, NaiveDate, Timelike, Utc};
-use serde_json::json;
+use crate::backup_archive::{is_archive, is_archive_path, read_archive, write_archive};
+use crate::backup_log::{backup_finished, backup_run_status, backup_started, BackupTrigger};
+use crate::backup_schedule::BackupSchedule;
+use crate::data::{get_app_data_dir, parse_settings_value, parse_storage_value, write_file_atomic};
+use crate::item_diff::APP_USAGE_FIELDS;
+use crate::models::{AppSettings, AppState, AppStorage, BackupRetention};
+use crate::settings_manager::apply_auto_start;
+use crate::store::Store;
+use chrono::{DateTime, Datelike, Local, NaiveDate, Timelike, Utc};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager, State};
 use tokio::time::sleep;
 
+/// 获取默认的自动备份目录
+pub fn get_default_backup_dir() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("backups"))
+}
+
+/// 获取设置中的备份目录，未设置时使用默认目录
+pub fn backup_dir_from_settings(settings: &AppSettings) -> Result<PathBuf, String> {
+    match settings.backup_dir.as_deref() {
+        Some(backup_dir) if !backup_dir.trim().is_empty() => Ok(PathBuf::from(backup_dir)),
+        _ => get_default_backup_dir(),
+    }
+}
+
+/// 获取当前使用的备份目录
+pub fn get_backup_dir(store: &Store) -> Result<PathBuf, String> {
+    backup_dir_from_settings(&store.settings()?)
+}
+
+/// 保留当前设置中的备份目录和自动备份记录
+///
+/// 这些字段描述的是本机的备份状态，恢复备份或导入设置时不应被覆盖。
+pub fn keep_backup_bookkeeping(settings: &mut AppSettings, current: &AppSettings) {
+    settings.backup_dir = current.backup_dir.clone();
+    settings.last_backup_time = current.last_backup_time;
+    settings.next_backup_time = current.next_backup_time;
+    settings.last_backup_hash = current.last_backup_hash.clone();
+}
+
+/// 数据文件损坏时查找备份的目录
+///
+/// 此时 Store 还没有加载，自定义备份目录直接从设置文件中读取，
+/// 读取失败时只使用默认目录。
+pub fn recovery_backup_dirs() -> Vec<PathBuf> {
+    let custom_dir = get_app_data_dir()
+        .ok()
+        .and_then(|data_dir| fs::read(data_dir.join("settings.json")).ok())
+        .and_then(|json_data| serde_json::from_slice::<serde_json::Value>(&json_data).ok())
+        .and_then(|settings| settings.get("backup_dir")?.as_str().map(PathBuf::from))
+        .filter(|backup_dir| !backup_dir.as_os_str().is_empty());
+
+    let mut dirs: Vec<PathBuf> = custom_dir.into_iter().collect();
+    if let Ok(default_dir) = get_default_backup_dir() {
+        if !dirs.contains(&default_dir) {
+            dirs.push(default_dir);
+        }
+    }
+    dirs
+}
+
+/// 校验备份目录：必须是已存在、可写入的绝对路径
+pub fn validate_backup_dir(backup_dir: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(backup_dir.trim());
+    if !path.is_absolute() {
+        return Err(format!("备份目录必须是绝对路径: {}", backup_dir));
+    }
+    if !path.is_dir() {
+        return Err(format!("备份目录不存在: {}", backup_dir));
+    }
+
+    // 通过写入并删除一个临时文件确认目录可写
+    let probe_path = path.join(format!(".lora_write_test_{}", std::process::id()));
+    fs::write(&probe_path, b"").map_err(|e| format!("备份目录不可写入: {} ({})", backup_dir, e))?;
+    let _ = fs::remove_file(&probe_path);
+
+    Ok(path)
+}
+
+/// 把备份文件（包括恢复前快照）从旧目录移动到新目录，返回移动的文件数量
+///
+/// 跨磁盘时无法直接重命名，改为复制后删除。新目录中已有同名文件时跳过。
+fn migrate_backup_files(from_dir: &Path, to_dir: &Path) -> Result<usize, String> {
+    let Ok(entries) = fs::read_dir(from_dir) else {
+        return Ok(0);
+    };
+
+    let mut moved = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let is_backup_file = (file_name.starts_with(AUTO_BACKUP_PREFIX)
+            || file_name.starts_with(PRE_RESTORE_PREFIX))
+            && has_backup_extension(file_name);
+        let target = to_dir.join(file_name);
+        if !path.is_file() || !is_backup_file || target.exists() {
+            continue;
+        }
+
+        if fs::rename(&path, &target).is_err() {
+            fs::copy(&path, &target)
+                .map_err(|e| format!("复制备份文件 {} 失败: {}", file_name, e))?;
+            // 保留原修改时间，保留策略按修改时间判断备份的新旧
+            if let Ok(modified) = entry.metadata().and_then(|metadata| metadata.modified()) {
+                let _ = fs::File::options()
+                    .write(true)
+                    .open(&target)
+                    .and_then(|file| file.set_modified(modified));
+            }
+            fs::remove_file(&path)
+                .map_err(|e| format!("删除旧备份文件 {} 失败: {}", file_name, e))?;
+        }
+        moved += 1;
+    }
+    Ok(moved)
+}
+
+/// 列出备份目录中的自动备份文件，按修改时间从新到旧排序
+pub fn list_backup_files(backup_dir: &Path) -> Vec<(PathBuf, SystemTime)> {
+    list_files_with_prefix(backup_dir, AUTO_BACKUP_PREFIX)
+}
+
+/// 列出备份目录中的恢复前快照，按修改时间从新到旧排序
+pub fn list_pre_restore_snapshots(backup_dir: &Path) -> Vec<(PathBuf, SystemTime)> {
+    list_files_with_prefix(backup_dir, PRE_RESTORE_PREFIX)
+}
+
+fn list_files_with_prefix(backup_dir: &Path, prefix: &str) -> Vec<(PathBuf, SystemTime)> {
+    let mut backup_files = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(backup_dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_file() {
+                if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
+                    if file_name.starts_with(prefix) && has_backup_extension(file_name) {
+                        if let Ok(metadata) = fs::metadata(&path) {
+                            if let Ok(modified) = metadata.modified() {
+                                backup_files.push((path, modified));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    backup_files.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+    backup_files
+}
+
+/// 定时备份任务单次等待的最长时间(秒)
+const MAX_SCHEDULER_SLEEP_SECONDS: i64 = 600;
+/// 定时备份任务读取设置失败后重试的等待时间(秒)
+const SETTINGS_RETRY_SECONDS: u64 = 60;
+
+/// 自动备份文件名前缀
+const AUTO_BACKUP_PREFIX: &str = "lora_auto_backup_";
+/// 恢复备份前自动保存的快照文件名前缀，不参与自动备份的保留策略
+const PRE_RESTORE_PREFIX: &str = "lora_pre_restore_";
+/// 最多保留的恢复前快照数量
+const MAX_PRE_RESTORE_SNAPSHOTS: usize = 5;
+
+/// 备份内容校验值中不包含的设置项：每次备份都会更新的备份记录
+const HASH_EXCLUDED_SETTINGS: [&str; 3] =
+    ["last_backup_time", "next_backup_time", "last_backup_hash"];
+
+/// 备份内容校验值中不包含的应用数据字段：切换分组时更新的当前选中分组
+const HASH_EXCLUDED_STORAGE: [&str; 1] = ["selected_category"];
+
+fn hash_values(storage: &Value, settings: &Value) -> String {
+    let mut settings = settings.clone();
+    if let Some(settings) = settings.as_object_mut() {
+        for key in HASH_EXCLUDED_SETTINGS {
+            settings.remove(key);
+        }
+    }
+
+    let mut hasher = Sha256::new();
+    let _ = serde_json::to_writer(&mut hasher, storage);
+    hasher.update(b"\n");
+    let _ = serde_json::to_writer(&mut hasher, &settings);
+    format!("{:x}", hasher.finalize())
+}
+
+/// 计算备份内容的校验值
+///
+/// 基于迁移前的原始应用数据和设置计算，不包含每次备份都会变化的备份时间，
+/// 也不包含启动项目和切换分组时更新的使用统计与当前选中分组，
+/// 因此数据没有变化时两次备份的校验值相同。
+pub fn content_hash(storage: &Value, settings: &Value) -> String {
+    let mut storage = storage.clone();
+    if let Some(storage) = storage.as_object_mut() {
+        for key in HASH_EXCLUDED_STORAGE {
+            storage.remove(key);
+        }
+        let apps = storage.get_mut("apps").and_then(Value::as_array_mut);
+        for app in apps.into_iter().flatten().filter_map(Value::as_object_mut) {
+            for key in APP_USAGE_FIELDS {
+                app.remove(*key);
+            }
+        }
+    }
+    hash_values(&storage, settings)
+}
+
+/// 原始数据的内容校验值，同时保留旧版本算法的结果
+///
+/// 旧版本的校验值包含使用统计和当前选中分组，只用于核对旧版本写入的备份。
+pub struct ContentHash {
+    pub current: String,
+    legacy: String,
+}
+
+impl ContentHash {
+    pub fn of(storage: &Value, settings: &Value) -> Self {
+        Self {
+            current: content_hash(storage, settings),
+            legacy: hash_values(storage, settings),
+        }
+    }
+
+    /// 备份中记录的校验值是否与内容一致
+    pub fn matches(&self, recorded: &str) -> bool {
+        recorded == self.current || recorded == self.legacy
+    }
+}
+
+/// 计算当前数据的备份内容校验值
+pub fn current_content_hash(
+    storage: &AppStorage,
+    settings: &AppSettings,
+) -> Result<String, String> {
+    let storage =
+        serde_json::to_value(storage).map_err(|e| format!("序列化备份数据失败: {}", e))?;
+    let settings =
+        serde_json::to_value(settings).map_err(|e| format!("序列化备份数据失败: {}", e))?;
+    Ok(content_hash(&storage, &settings))
+}
+
+/// 备份文件格式为 JSON 或 zip 归档
+fn has_backup_extension(name: &str) -> bool {
+    name.ends_with(".json") || name.ends_with(".zip")
+}
+
+/// 备份文件中的数据，已迁移到当前版本
+pub struct BackupContents {
+    pub storage: AppStorage,
+    pub settings: AppSettings,
+    pub backup_time: Option<i64>,
+    pub backup_type: Option<String>,
+    pub version: Option<String>,
+    /// 备份中记录的内容校验值，已与实际内容核对；旧版本的备份没有校验值
+    pub content_hash: Option<String>,
+}
+
+/// 把应用数据和设置写入备份文件，扩展名为 .zip 时写入归档
+///
+/// 返回备份的内容校验值。
+fn write_backup_file(
+    path: &Path,
+    storage: &AppStorage,
+    settings: &AppSettings,
+    backup_type: &str,
+) -> Result<String, String> {
+    let content_hash = current_content_hash(storage, settings)?;
+    if is_archive_path(path) {
+        write_archive(
+            path,
+            Some(storage),
+            Some(settings),
+            json!({ "backup_type": backup_type, "content_hash": content_hash }),
+        )?;
+        return Ok(content_hash);
+    }
+
+    let backup_data = json!({"storage": storage, "settings": settings, "backup_time": Local::now().timestamp(), "backup_type": backup_type, "version": env!("CARGO_PKG_VERSION"), "content_hash": content_hash});
+    let json_data = serde_json::to_string_pretty(&backup_data)
+        .map_err(|e| format!("序列化备份数据失败: {}", e))?;
+    write_file_atomic(path, json_data.as_bytes())
+        .map_err(|e| format!("写入备份文件失败: {}", e))?;
+    Ok(content_hash)
+}
+
+/// 根据文件名找到备份目录中的备份文件，拒绝目录外的路径
+pub fn resolve_backup_path(backup_dir: &Path, name: &str) -> Result<PathBuf, String> {
+    let is_plain_name = Path::new(name).file_name().and_then(|n| n.to_str()) == Some(name);
+    let is_backup_name = (name.starts_with(AUTO_BACKUP_PREFIX)
+        || name.starts_with(PRE_RESTORE_PREFIX))
+        && has_backup_extension(name);
+    if !is_plain_name || !is_backup_name {
+        return Err(format!("无效的备份文件名: {}", name));
+    }
+
+    let path = backup_dir.join(name);
+    if !path.is_file() {
+        return Err(format!("备份文件不存在: {}", name));
+    }
+    Ok(path)
+}
+
+/// 核对备份中记录的内容校验值，返回按当前算法计算的校验值
+fn check_content_hash(
+    recorded: Option<&str>,
+    actual: Option<ContentHash>,
+) -> Result<Option<String>, String> {
+    match (recorded, actual) {
+        (Some(recorded), Some(actual)) if actual.matches(recorded) => Ok(Some(actual.current)),
+        (Some(_), _) => Err("备份内容校验失败，备份可能已损坏".to_string()),
+        (None, _) => Ok(None),
+    }
+}
+
+/// 读取并校验备份文件，支持归档和 JSON 两种格式
+pub fn load_backup(path: &Path) -> Result<BackupContents, String> {
+    let json_data = fs::read(path).map_err(|e| format!("读取备份文件失败: {}", e))?;
+    if is_archive(&json_data) {
+        let archive = read_archive(&json_data)?;
+        let manifest_str = |key: &str| {
+            archive
+                .manifest
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+        };
+        let content_hash = check_content_hash(
+            archive
+                .manifest
+                .get("content_hash")
+                .and_then(|v| v.as_str()),
+            archive.content_hash,
+        )?;
+        return Ok(BackupContents {
+            content_hash,
+            backup_time: archive.manifest.get("created_at").and_then(|v| v.as_i64()),
+            backup_type: manifest_str("backup_type"),
+            version: manifest_str("version"),
+            storage: archive.storage.ok_or("备份文件中没有应用数据")?,
+            settings: archive.settings.ok_or("备份文件中没有设置数据")?,
+        });
+    }
+
+    let backup_data: serde_json::Value =
+        serde_json::from_slice(&json_data).map_err(|e| format!("解析备份文件失败: {}", e))?;
+
+    let content_hash = check_content_hash(
+        backup_data.get("content_hash").and_then(|v| v.as_str()),
+        match (backup_data.get("storage"), backup_data.get("settings")) {
+            (Some(storage), Some(settings)) => Some(ContentHash::of(storage, settings)),
+            _ => None,
+        },
+    )?;
+
+    let storage = backup_data
+        .get("storage")
+        .cloned()
+        .ok_or("备份文件中没有应用数据")
+        .and_then(|value| parse_storage_value(value).map_err(|_| "备份中的应用数据无效"))?;
+    let settings = backup_data
+        .get("settings")
+        .cloned()
+        .ok_or("备份文件中没有设置数据")
+        .and_then(|value| parse_settings_value(value).map_err(|_| "备份中的设置数据无效"))?;
+
+    Ok(BackupContents {
+        storage,
+        settings,
+        backup_time: backup_data.get("backup_time").and_then(|v| v.as_i64()),
+        backup_type: backup_data
+            .get("backup_type")
+            .and_then(|v| v.as_str())
+            .map(str::to_string),
+        version: backup_data
+            .get("version")
+            .and_then(|v| v.as_str())
+            .map(str::to_string),
+        content_hash,
+    })
+}
+
+/// 保存恢复前的快照，并清理多余的旧快照
+fn save_pre_restore_snapshot(
+    backup_dir: &Path,
+    storage: &AppStorage,
+    settings: &AppSettings,
+) -> Result<PathBuf, String> {
+    fs::create_dir_all(backup_dir).map_err(|e| format!("创建备份目录失败: {}", e))?;
+    let snapshot_path = backup_dir.join(format!(
+        "{}{}.zip",
+        PRE_RESTORE_PREFIX,
+        Local::now().format("%Y%m%d_%H%M%S")
+    ));
+    write_backup_file(&snapshot_path, storage, settings, "pre_restore")?;
+
+    let snapshots = list_pre_restore_snapshots(backup_dir);
+    for (path, _) in snapshots.iter().skip(MAX_PRE_RESTORE_SNAPSHOTS) {
+        let _ = fs::remove_file(path);
+    }
+
+    Ok(snapshot_path)
+}
+
+/// 清理多余的原始数据快照目录，与恢复前快照一样最多保留
+/// `MAX_PRE_RESTORE_SNAPSHOTS` 个
+///
+/// 目录名中的时间戳按字典序即为时间顺序，按名称从新到旧排序。
+fn prune_pre_restore_raw_dirs(backup_dir: &Path) {
+    let Ok(entries) = fs::read_dir(backup_dir) else {
+        return;
+    };
+    let mut raw_dirs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_dir()
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| {
+                        name.starts_with(PRE_RESTORE_PREFIX) && name.ends_with("_raw")
+                    })
+        })
+        .collect();
+    raw_dirs.sort_by(|a, b| b.cmp(a));
+
+    for path in raw_dirs.iter().skip(MAX_PRE_RESTORE_SNAPSHOTS) {
+        let _ = fs::remove_dir_all(path);
+    }
+}
+
+/// 当前数据无法读取时，把磁盘上的原始数据文件复制到恢复前快照目录中
+///
+/// 原始文件可能已经损坏，不能打包成备份，只原样保留以便手动恢复。
+/// 同时清理多余的旧快照目录。
+fn save_pre_restore_raw_files(backup_dir: &Path) -> Result<PathBuf, String> {
+    let snapshot_dir = backup_dir.join(format!(
+        "{}{}_raw",
+        PRE_RESTORE_PREFIX,
+        Local::now().format("%Y%m%d_%H%M%S")
+    ));
+    fs::create_dir_all(&snapshot_dir).map_err(|e| format!("创建快照目录失败: {}", e))?;
+
+    let data_dir = get_app_data_dir()?;
+    for file_name in ["apps.json", "settings.json"] {
+        let source = data_dir.join(file_name);
+        if source.is_file() {
+            fs::copy(&source, snapshot_dir.join(file_name))
+                .map_err(|e| format!("保存 {} 失败: {}", file_name, e))?;
+        }
+    }
+
+    prune_pre_restore_raw_dirs(backup_dir);
+    Ok(snapshot_dir)
+}
+
+/// 按保留策略计算需要清理的备份文件
+///
+/// `backup_files` 需要按从新到旧排序。最近的 `keep_last` 份始终保留；在保留的
+/// 天数、周数、月数范围内，每天、每周（周一开始）、每月各保留最新的一份。
+pub fn backups_to_prune(
+    backup_files: &[(PathBuf, SystemTime)],
+    retention: &BackupRetention,
+    now: DateTime<Local>,
+) -> Vec<PathBuf> {
+    let today = now.date_naive();
+    let this_week_start =
+        today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64);
+    let this_month = today.year() * 12 + today.month0() as i32;
+
+    let mut kept_days = HashSet::new();
+    let mut kept_weeks = HashSet::new();
+    let mut kept_months = HashSet::new();
+    let mut to_prune = Vec::new();
+
+    for (index, (path, modified)) in backup_files.iter().enumerate() {
+        let date = DateTime::<Local>::from(*modified).date_naive();
+        let week_start =
+            date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64);
+        let month = date.year() * 12 + date.month0() as i32;
+
+        let days_ago = (today - date).num_days();
+        let weeks_ago = (this_week_start - week_start).num_days() / 7;
+        let months_ago = this_month - month;
+
+        // 每个条件都要执行，以便记录该时间段已经保留了一份
+        let keep_recent = index < retention.keep_last as usize;
+        let keep_daily = days_ago < retention.keep_daily_days as i64 && kept_days.insert(date);
+        let keep_weekly =
+            weeks_ago < retention.keep_weekly_weeks as i64 && kept_weeks.insert(week_start);
+        let keep_monthly =
+            months_ago < retention.keep_monthly_months as i32 && kept_months.insert(month);
+
+        if !(keep_recent || keep_daily || keep_weekly || keep_monthly) {
+            to_prune.push(path.clone());
+        }
+    }
+
+    to_prune
+}
+
 #[allow(dead_code)]
 pub struct BackupManager {
     #[allow(dead_code)]
@@ -20,224 +521,607 @@ impl BackupManager {
         }
     }
 
-    pub async fn perform_backup() -> Result<String, String> {
+    pub async fn perform_backup(store: &Store) -> Result<String, String> {
         println!("开始执行定时备份...");
 
-        let data_dir = get_app_data_dir()?;
-        let backup_dir = data_dir.join("backups");
+        let storage = store.storage()?;
+        let settings = store.settings()?;
+        Self::write_auto_backup(store, &storage, &settings, "auto")
+    }
+
+    /// 执行一次备份，广播开始和结束事件并写入备份记录
+    ///
+    /// 修改后备份在数据没有变化时跳过，返回 None。
+    pub async fn run_backup(
+        app_handle: &AppHandle,
+        trigger: BackupTrigger,
+    ) -> Result<Option<String>, String> {
+        let store = app_handle.state::<AppState>().store.clone();
+        let started_at = backup_started(app_handle, trigger);
+        let result = match trigger {
+            BackupTrigger::Change => Self::perform_backup_if_changed(&store).await,
+            BackupTrigger::Scheduled | BackupTrigger::Manual => {
+                Self::perform_backup(&store).await.map(Some)
+            }
+        };
+        backup_finished(app_handle, trigger, started_at, &result);
+        result
+    }
+
+    /// 数据自上次备份以来有变化时才执行备份，没有变化时返回 None
+    pub async fn perform_backup_if_changed(store: &Store) -> Result<Option<String>, String> {
+        let storage = store.storage()?;
+        let settings = store.settings()?;
+
+        let content_hash = current_content_hash(&storage, &settings)?;
+        if settings.last_backup_hash.as_deref() == Some(content_hash.as_str()) {
+            return Ok(None);
+        }
+        Self::write_auto_backup(store, &storage, &settings, "change").map(Some)
+    }
+
+    /// 写入一份自动备份，记录备份时间和内容校验值，并按保留策略清理旧备份
+    fn write_auto_backup(
+        store: &Store,
+        storage: &AppStorage,
+        settings: &AppSettings,
+        backup_type: &str,
+    ) -> Result<String, String> {
+        let backup_dir = backup_dir_from_settings(settings)?;
         if !backup_dir.exists() {
+            // 自定义目录可能位于未挂载的网络位置，不自动创建
+            if settings.backup_dir.is_some() {
+                return Err(format!("备份目录不存在: {}", backup_dir.display()));
+            }
             fs::create_dir_all(&backup_dir).map_err(|e| format!("创建备份目录失败: {}", e))?;
         }
 
         let now = Local::now();
         let timestamp = now.format("%Y%m%d_%H%M%S").to_string();
-        let backup_file_path = backup_dir.join(format!("lora_auto_backup_{}.json", timestamp));
 
-        let storage = load_app_data()?;
-        let settings = load_app_settings()?;
+        let extension = match settings.backup_format.as_deref() {
+            Some("json") => "json",
+            _ => "zip",
+        };
+        let backup_file_path =
+            backup_dir.join(format!("{}{}.{}", AUTO_BACKUP_PREFIX, timestamp, extension));
 
-        let backup_data = json!({"storage": storage, "settings": settings, "backup_time": now.timestamp(), "backup_type": "auto", "version": env!("CARGO_PKG_VERSION")});
-        let json_data = serde_json::to_string_pretty(&backup_data)
-            .map_err(|e| format!("序列化备份数据失败: {}", e))?;
-        fs::write(&backup_file_path, json_data).map_err(|e| format!("写入备份文件失败: {}", e))?;
+        let content_hash = write_backup_file(&backup_file_path, storage, settings, backup_type)?;
+        store.update_settings(|settings| {
+            settings.last_backup_time = Some(now.timestamp());
+            settings.last_backup_hash = Some(content_hash);
+            Ok(())
+        })?;
 
-        // cleanup: keep last 10
-        let _ = Self::cleanup_old_backups(&backup_dir);
+        let retention = settings.backup_retention.clone().unwrap_or_default();
+        let _ = Self::cleanup_old_backups(&backup_dir, &retention);
 
         Ok(format!("自动备份成功完成: {}", backup_file_path.display()))
     }
 
-    fn cleanup_old_backups(backup_dir: &Path) -> Result<(), String> {
-        let mut backup_files = Vec::new();
-
-        if let Ok(entries) = fs::read_dir(backup_dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.is_file() {
-                    if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
-                        if file_name.starts_with("lora_auto_backup_")
-                            && file_name.ends_with(".json")
-                        {
-                            if let Ok(metadata) = fs::metadata(&path) {
-                                if let Ok(modified) = metadata.modified() {
-                                    backup_files.push((path, modified));
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        backup_files.sort_by(|a, b| b.1.cmp(&a.1));
+    fn cleanup_old_backups(backup_dir: &Path, retention: &BackupRetention) -> Result<(), String> {
+        let backup_files = list_backup_files(backup_dir);
 
-        if backup_files.len() > 10 {
-            for (path, _) in backup_files.iter().skip(10) {
-                let _ = fs::remove_file(path);
-            }
+        for path in backups_to_prune(&backup_files, retention, Local::now()) {
+            let _ = fs::remove_file(path);
         }
 
         Ok(())
     }
 
+    /// 按备份间隔设置计算下次备份时间，设置无法解析时返回错误
     fn calculate_next_backup_time(interval: &str) -> Result<chrono::DateTime<Local>, String> {
-        let now = Local::now();
+        BackupSchedule::parse(interval)?.next_after(Local::now())
+    }
 
-        match interval {
-            "daily" => {
-                let next_day = now.date_naive().succ_opt().ok_or("计算明天日期失败")?;
-                let next_time = next_day
-                    .and_hms_opt(now.hour(), now.minute(), now.second())
-                    .ok_or("构建明天时间失败")?;
-                Ok(next_time
-                    .and_local_timezone(Local)
-                    .single()
-                    .ok_or("构建本地时间失败")?)
-            }
-            "weekly" => {
-                let days_until_next_week = 7 - now.weekday().num_days_from_monday() % 7;
-                let next_week_date =
-                    now.date_naive() + chrono::Duration::days(days_until_next_week as i64);
-                let next_time = next_week_date
-                    .and_hms_opt(now.hour(), now.minute(), now.second())
-                    .ok_or("构建下周时间失败")?;
-                Ok(next_time
-                    .and_local_timezone(Local)
-                    .single()
-                    .ok_or("构建本地时间失败")?)
-            }
-            "monthly" => {
-                let next_month = if now.month() == 12 {
-                    now.year() + 1
-                } else {
-                    now.year()
-                };
-                let next_month_num = if now.month() == 12 {
-                    1
-                } else {
-                    now.month() + 1
-                };
-                let next_day = if let Some(date) =
-                    NaiveDate::from_ymd_opt(next_month, next_month_num, now.day())
-                {
-                    date
-                } else {
-                    NaiveDate::from_ymd_opt(next_month, next_month_num + 1, 1)
-                        .and_then(|d| d.pred_opt())
-                        .ok_or("计算下个月最后一天失败")?
-                };
-                let next_time = next_day
-                    .and_hms_opt(now.hour(), now.minute(), now.second())
-                    .ok_or("构建下个月时间失败")?;
-                Ok(next_time
-                    .and_local_timezone(Local)
-                    .single()
-                    .ok_or("构建本地时间失败")?)
-            }
-            _ => Err("不支持的备份间隔".to_string()),
+    /// 重新启动定时备份任务
+    ///
+    /// 取消正在等待的任务并按当前设置重新计划，未启用自动备份时只取消。
+    /// 自动备份开关、备份间隔或下次备份时间变化后调用，新设置立即生效。
+    pub fn restart_scheduler(app_handle: &AppHandle) {
+        let state = app_handle.state::<AppState>();
+        let mut backup_handle = state
+            .backup_handle
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(previous) = backup_handle.take() {
+            previous.abort();
         }
+        *state
+            .backup_next_run
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = None;
+
+        let enabled = state
+            .store
+            .settings()
+            .is_ok_and(|settings| settings.auto_backup.unwrap_or(false));
+        if !enabled {
+            return;
+        }
+
+        let app_handle = app_handle.clone();
+        let store = state.store.clone();
+        let next_run = state.backup_next_run.clone();
+        *backup_handle = Some(tauri::async_runtime::spawn(Self::run_scheduler(
+            app_handle, store, next_run,
+        )));
     }
 
-    pub async fn start_backup_task(_app_handle: tauri::AppHandle) -> Result<(), String> {
+    /// 定时备份循环，出错时记录日志并继续按计划运行，只在设置变化时被取消
+    async fn run_scheduler(
+        app_handle: AppHandle,
+        store: Arc<Store>,
+        next_run: Arc<Mutex<Option<i64>>>,
+    ) {
+        // 下次备份时间写入设置失败时保存在这里，避免按旧的计划时间重复备份
+        let mut unsaved_next_time: Option<i64> = None;
         loop {
-            let settings = load_app_settings()?;
+            let settings = match store.settings() {
+                Ok(settings) => settings,
+                Err(error) => {
+                    eprintln!("读取设置失败，稍后重试定时备份: {}", error);
+                    sleep(Duration::from_secs(SETTINGS_RETRY_SECONDS)).await;
+                    continue;
+                }
+            };
+            let now = Local::now();
+
+            // 没有计划时间(首次启用)时立即备份一次
+            let planned_backup_time = unsaved_next_time
+                .or(settings.next_backup_time)
+                .and_then(|t| chrono::DateTime::<Utc>::from_timestamp(t, 0))
+                .map(|dt| dt.with_timezone(&Local))
+                .unwrap_or(now);
+            *next_run.lock().unwrap_or_else(PoisonError::into_inner) =
+                Some(planned_backup_time.timestamp());
 
-            // 检查是否启用了自动备份
-            if !settings.auto_backup.unwrap_or(false) {
-                sleep(Duration::from_secs(3600)).await;
+            if now < planned_backup_time {
+                // 系统休眠期间计时可能暂停，分段等待并重新检查时间
+                let sleep_seconds = planned_backup_time
+                    .signed_duration_since(now)
+                    .num_seconds()
+                    .clamp(1, MAX_SCHEDULER_SLEEP_SECONDS);
+                sleep(Duration::from_secs(sleep_seconds as u64)).await;
                 continue;
             }
 
-            let backup_interval = settings.backup_interval.unwrap_or("weekly".to_string());
-            let now = Local::now();
+            // 失败原因已写入备份记录，下次仍按计划重试
+            let _ = Self::run_backup(&app_handle, BackupTrigger::Scheduled).await;
 
-            // 检查是否有计划的备份时间
-            let planned_backup_time = settings
-                .next_backup_time
-                .and_then(|t| chrono::DateTime::<Utc>::from_timestamp(t, 0))
-                .map(|dt| dt.with_timezone(&Local));
+            // 更新下次备份时间，上次备份时间在备份成功时已记录
+            let backup_interval = settings.backup_interval.as_deref().unwrap_or("weekly");
+            let next_time =
+                Self::calculate_next_backup_time(backup_interval).unwrap_or_else(|error| {
+                    eprintln!("计算下次备份时间失败，一周后再次备份: {}", error);
+                    Local::now() + chrono::Duration::days(7)
+                });
+            let saved = store.update_settings(|settings| {
+                settings.next_backup_time = Some(next_time.timestamp());
+                Ok(())
+            });
+            unsaved_next_time = match saved {
+                Ok(()) => None,
+                Err(error) => {
+                    eprintln!("保存下次备份时间失败，下次备份后重试: {}", error);
+                    Some(next_time.timestamp())
+                }
+            };
 
-            let should_backup_now = match planned_backup_time {
-                Some(planned_time) => {
-                    // 有计划时间,检查是否到了
-                    now >= planned_time
+            println!("下次备份时间: {}", next_time.format("%Y-%m-%d %H:%M:%S"));
+        }
+    }
+
+    /// 修改后自动备份任务
+    ///
+    /// 应用数据修改后等待一段没有新修改的时间再备份，并保证与上次备份之间
+    /// 至少间隔设置的最短时间。数据与上次备份相同时不写入新的备份。
+    pub async fn start_change_backup_task(app_handle: tauri::AppHandle) -> Result<(), String> {
+        let store = app_handle.state::<AppState>().store.clone();
+
+        loop {
+            store.library_changed().notified().await;
+
+            // 等待期间每次新的修改都会重新开始计时
+            loop {
+                let debounce_seconds = match store.settings() {
+                    Ok(settings) => settings.backup_debounce_seconds.unwrap_or(300),
+                    Err(error) => {
+                        eprintln!("读取设置失败，使用默认的等待时间: {}", error);
+                        300
+                    }
+                };
+                tokio::select! {
+                    _ = store.library_changed().notified() => continue,
+                    _ = sleep(Duration::from_secs(debounce_seconds as u64)) => break,
                 }
-                None => {
-                    // 没有计划时间(首次启动),立即备份一次
-                    true
+            }
+
+            let settings = match store.settings() {
+                Ok(settings) => settings,
+                Err(error) => {
+                    eprintln!("读取设置失败，跳过本次修改后备份: {}", error);
+                    continue;
                 }
             };
+            if !settings.backup_on_change.unwrap_or(false) {
+                continue;
+            }
 
-            if should_backup_now {
-                // 执行备份
-                let _ = Self::perform_backup().await;
-
-                // 更新下次备份时间
-                let next_time = Self::calculate_next_backup_time(&backup_interval)?;
-                let mut settings = load_app_settings()?;
-                settings.last_backup_time = Some(now.timestamp());
-                settings.next_backup_time = Some(next_time.timestamp());
-                save_app_settings(settings)?;
-
-                println!(
-                    "备份完成,下次备份时间: {}",
-                    next_time.format("%Y-%m-%d %H:%M:%S")
-                );
-            } else {
-                // 计算还需要等待多久
-                let duration = planned_backup_time.unwrap().signed_duration_since(now);
-                let sleep_seconds = duration.num_seconds().min(3600).max(0) as u64; // 最多等待1小时后重新检查
-                println!("距离下次备份还有 {} 秒,等待中...", sleep_seconds);
-                sleep(Duration::from_secs(sleep_seconds)).await;
+            let min_interval = settings.backup_min_interval_minutes.unwrap_or(30) as i64 * 60;
+            if let Some(last_backup_time) = settings.last_backup_time {
+                let wait_seconds = last_backup_time + min_interval - Local::now().timestamp();
+                if wait_seconds > 0 {
+                    println!("距离上次备份不足最短间隔，{} 秒后备份", wait_seconds);
+                    sleep(Duration::from_secs(wait_seconds as u64)).await;
+                }
             }
+
+            let _ = Self::run_backup(&app_handle, BackupTrigger::Change).await;
         }
     }
 }
 
 #[tauri::command]
-pub async fn manual_backup() -> Result<String, String> {
-    BackupManager::perform_backup().await
+pub async fn manual_backup(app: AppHandle) -> Result<String, String> {
+    BackupManager::run_backup(&app, BackupTrigger::Manual)
+        .await
+        .map(Option::unwrap_or_default)
 }
 
 #[tauri::command]
-pub fn get_backup_status() -> Result<serde_json::Value, String> {
-    let data_dir = get_app_data_dir()?;
-    let backup_dir = data_dir.join("backups");
+pub fn get_backup_status(state: State<'_, AppState>) -> Result<serde_json::Value, String> {
+    let settings = state.store.settings().ok();
+    let backup_dir = match &settings {
+        Some(settings) => backup_dir_from_settings(settings)?,
+        None => get_default_backup_dir()?,
+    };
+    let retention = settings
+        .as_ref()
+        .and_then(|settings| settings.backup_retention.clone())
+        .unwrap_or_default();
 
-    let mut backup_files = Vec::new();
+    // 清理在下一次备份之后执行，按届时的情况预测会被清理的文件
+    let next_backup_time = settings
+        .as_ref()
+        .and_then(|settings| settings.next_backup_time)
+        .and_then(|t| DateTime::<Utc>::from_timestamp(t, 0))
+        .map(|dt| dt.with_timezone(&Local))
+        .filter(|next_time| *next_time > Local::now())
+        .unwrap_or_else(Local::now);
+    let existing_files = list_backup_files(&backup_dir);
+    let mut files_after_next_backup = vec![(PathBuf::new(), SystemTime::from(next_backup_time))];
+    files_after_next_backup.extend(existing_files.iter().cloned());
+    let prune_next = backups_to_prune(&files_after_next_backup, &retention, next_backup_time);
 
-    if backup_dir.exists() {
-        if let Ok(entries) = fs::read_dir(&backup_dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.is_file() {
-                    if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
-                        if file_name.starts_with("lora_auto_backup_")
-                            && file_name.ends_with(".json")
-                        {
-                            if let Ok(metadata) = fs::metadata(&path) {
-                                if let Ok(modified) = metadata.modified() {
-                                    if let Ok(modified_datetime) =
-                                        modified.duration_since(std::time::UNIX_EPOCH)
-                                    {
-                                        backup_files.push(serde_json::json!({"name": file_name, "path": path.to_string_lossy(), "size": metadata.len(), "modified": modified_datetime.as_secs()}));
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+    let backup_files: Vec<serde_json::Value> = existing_files
+        .into_iter()
+        .filter_map(|(path, modified)| {
+            let file_name = path.file_name()?.to_str()?;
+            let size = fs::metadata(&path).ok()?.len();
+            let modified = modified.duration_since(std::time::UNIX_EPOCH).ok()?;
+            let will_be_pruned = prune_next.contains(&path);
+            Some(serde_json::json!({"name": file_name, "path": path.to_string_lossy(), "size": size, "modified": modified.as_secs(), "will_be_pruned": will_be_pruned}))
+        })
+        .collect();
+    // 定时备份任务实际计划的下次运行时间，未启用自动备份时为空
+    let next_run = *state
+        .backup_next_run
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    // 任务因错误结束后句柄仍然保留，需要检查任务是否已经结束
+    let scheduler_running = state
+        .backup_handle
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_ref()
+        .is_some_and(|handle| !handle.inner().is_finished());
+
+    let prune_next_names: Vec<String> = prune_next
+        .iter()
+        .filter_map(|path| Some(path.file_name()?.to_str()?.to_string()))
+        .collect();
+
+    Ok(
+        serde_json::json!({"backup_dir": backup_dir.to_string_lossy(), "backup_dir_available": backup_dir.is_dir(), "default_backup_dir": get_default_backup_dir()?.to_string_lossy(), "backup_files": backup_files, "total_count": backup_files.len(), "retention": retention, "prune_next": prune_next_names, "last_verification": crate::backup_verify::load_last_verification(), "scheduler_running": scheduler_running, "next_run": next_run, "status": backup_run_status()}),
+    )
+}
+
+/// 从备份恢复应用数据和设置
+///
+/// 恢复前会校验备份文件并把当前数据保存为快照；写入数据或重新注册全局
+/// 快捷键失败时回滚到恢复前的状态。
+///
+/// 当前数据加载失败时也可以恢复：备份目录改为从设置文件中直接读取，
+/// 快照只复制原始数据文件，失败时无法回滚。
+#[tauri::command]
+pub fn restore_backup(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<String, String> {
+    let store = &state.store;
+    let backup_dir = match store.settings() {
+        Ok(settings) => backup_dir_from_settings(&settings)?,
+        Err(_) => recovery_backup_dirs()
+            .into_iter()
+            .find(|backup_dir| resolve_backup_path(backup_dir, &name).is_ok())
+            .ok_or_else(|| format!("备份文件不存在: {}", name))?,
+    };
+    let backup_path = resolve_backup_path(&backup_dir, &name)?;
+    let backup = load_backup(&backup_path)?;
+
+    let previous = store
+        .storage()
+        .and_then(|storage| Ok((storage, store.settings()?)));
+    let snapshot_path = match &previous {
+        Ok((storage, settings)) => save_pre_restore_snapshot(&backup_dir, storage, settings)?,
+        Err(_) => save_pre_restore_raw_files(&backup_dir)?,
+    };
+
+    let mut restored_settings = backup.settings;
+    if let Ok(current_settings) = store.settings() {
+        keep_backup_bookkeeping(&mut restored_settings, &current_settings);
+    }
+
+    let mut library_entry = None;
+    let restore = || -> Result<(), String> {
+        library_entry = store.replace_library("恢复备份", backup.storage)?;
+        apply_auto_start(&restored_settings)?;
+        store.replace_settings(restored_settings)?;
+        crate::system_integration::refresh_global_shortcuts(&app)
+    };
+
+    if let Err(error) = restore() {
+        let rollback = match library_entry {
+            Some(entry_id) => store.rollback_library(entry_id),
+            None => Ok(()),
+        }
+        .and_then(|_| match previous {
+            Ok((_, previous_settings)) => {
+                let _ = apply_auto_start(&previous_settings);
+                store.replace_settings(previous_settings)
             }
+            Err(load_error) => Err(format!("恢复前的数据无法读取: {}", load_error)),
+        });
+        let _ = crate::system_integration::refresh_global_shortcuts(&app);
+        BackupManager::restart_scheduler(&app);
+        return Err(match rollback {
+            Ok(()) => format!("恢复备份失败，已回滚到恢复前的状态: {}", error),
+            Err(rollback_error) => format!(
+                "恢复备份失败: {}；回滚失败: {}，恢复前的数据已保存在 {}",
+                error,
+                rollback_error,
+                snapshot_path.display()
+            ),
+        });
+    }
+
+    // 恢复的设置中可能有不同的自动备份计划
+    BackupManager::restart_scheduler(&app);
+    Ok(format!("已从备份 {} 恢复数据", name))
+}
+
+/// 更新备份目录
+///
+/// `backup_dir` 为空时恢复使用默认目录。`migrate_existing` 为 true 时把旧目录
+/// 中已有的备份移动到新目录。
+#[tauri::command]
+pub fn update_backup_dir(
+    state: State<'_, AppState>,
+    backup_dir: Option<String>,
+    migrate_existing: Option<bool>,
+) -> Result<String, String> {
+    let backup_dir = backup_dir.filter(|backup_dir| !backup_dir.trim().is_empty());
+    let new_dir = match &backup_dir {
+        Some(backup_dir) => validate_backup_dir(backup_dir)?,
+        None => {
+            let default_dir = get_default_backup_dir()?;
+            fs::create_dir_all(&default_dir).map_err(|e| format!("创建备份目录失败: {}", e))?;
+            default_dir
         }
+    };
+
+    let old_dir = get_backup_dir(&state.store)?;
+    state.store.update_settings(|settings| {
+        settings.backup_dir = backup_dir.map(|_| new_dir.to_string_lossy().to_string());
+        Ok(())
+    })?;
+
+    if old_dir == new_dir || !migrate_existing.unwrap_or(false) {
+        return Ok("备份目录已更新".to_string());
     }
 
-    backup_files.sort_by(|a, b| {
-        let a_time = a.get("modified").and_then(|v| v.as_u64()).unwrap_or(0);
-        let b_time = b.get("modified").and_then(|v| v.as_u64()).unwrap_or(0);
-        b_time.cmp(&a_time)
-    });
+    let moved = migrate_backup_files(&old_dir, &new_dir)
+        .map_err(|e| format!("备份目录已更新，但移动已有备份时出错: {}", e))?;
+    Ok(format!("备份目录已更新，已移动 {} 个备份文件", moved))
+}
 
-    Ok(
-        serde_json::json!({"backup_dir": backup_dir.to_string_lossy(), "backup_files": backup_files, "total_count": backup_files.len()}),
-    )
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestDir;
+    use chrono::TimeZone;
+
+    fn at(month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2024, month, day, hour, minute, 0)
+            .single()
+            .unwrap()
+    }
+
+    fn retention(keep_last: u32, days: u32, weeks: u32, months: u32) -> BackupRetention {
+        BackupRetention {
+            keep_last,
+            keep_daily_days: days,
+            keep_weekly_weeks: weeks,
+            keep_monthly_months: months,
+        }
+    }
+
+    /// 按给定的名称和时间（从新到旧）构造备份列表，返回会被清理的名称
+    fn pruned(
+        backups: &[(&str, DateTime<Local>)],
+        retention: &BackupRetention,
+        now: DateTime<Local>,
+    ) -> Vec<String> {
+        let backup_files: Vec<_> = backups
+            .iter()
+            .map(|(name, time)| (PathBuf::from(name), SystemTime::from(*time)))
+            .collect();
+        backups_to_prune(&backup_files, retention, now)
+            .into_iter()
+            .map(|path| path.display().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn keeps_the_latest_backups() {
+        let now = at(6, 15, 12, 0);
+        let backups = [
+            ("a", at(6, 15, 11, 0)),
+            ("b", at(6, 15, 10, 0)),
+            ("c", at(6, 1, 10, 0)),
+            ("d", at(1, 1, 10, 0)),
+        ];
+
+        assert_eq!(pruned(&backups, &retention(2, 0, 0, 0), now), ["c", "d"]);
+        assert!(pruned(&backups, &retention(10, 0, 0, 0), now).is_empty());
+    }
+
+    #[test]
+    fn keeps_the_newest_backup_of_each_day() {
+        let now = at(6, 15, 12, 0);
+        let backups = [
+            ("today_late", at(6, 15, 11, 0)),
+            ("today_early", at(6, 15, 0, 0)),
+            ("yesterday_late", at(6, 14, 23, 59)),
+            ("yesterday_early", at(6, 14, 8, 0)),
+            ("two_days_ago", at(6, 13, 0, 0)),
+            ("three_days_ago", at(6, 12, 23, 59)),
+        ];
+
+        assert_eq!(
+            pruned(&backups, &retention(0, 3, 0, 0), now),
+            ["today_early", "yesterday_early", "three_days_ago"]
+        );
+    }
+
+    #[test]
+    fn keeps_the_newest_backup_of_each_week() {
+        // 2024-06-15 是周六，本周从 06-10（周一）开始
+        let now = at(6, 15, 12, 0);
+        let backups = [
+            ("saturday", at(6, 15, 10, 0)),
+            ("monday", at(6, 10, 0, 0)),
+            ("last_sunday", at(6, 9, 23, 59)),
+            ("last_monday", at(6, 3, 0, 0)),
+            ("two_weeks_ago", at(6, 2, 23, 59)),
+        ];
+
+        assert_eq!(
+            pruned(&backups, &retention(0, 0, 2, 0), now),
+            ["monday", "last_monday", "two_weeks_ago"]
+        );
+    }
+
+    #[test]
+    fn keeps_the_newest_backup_of_each_month() {
+        let now = at(6, 15, 12, 0);
+        let backups = [
+            ("june", at(6, 14, 10, 0)),
+            ("june_first", at(6, 1, 0, 0)),
+            ("may_last", at(5, 31, 23, 59)),
+            ("may_first", at(5, 1, 0, 0)),
+            ("april", at(4, 30, 23, 59)),
+        ];
+
+        assert_eq!(
+            pruned(&backups, &retention(0, 0, 0, 2), now),
+            ["june_first", "may_first", "april"]
+        );
+    }
+
+    #[test]
+    fn buckets_are_combined() {
+        let now = at(6, 15, 12, 0);
+        let backups = [
+            ("today", at(6, 15, 11, 0)),
+            ("today_early", at(6, 15, 1, 0)),
+            ("last_week", at(6, 5, 10, 0)),
+            ("last_month", at(5, 20, 10, 0)),
+            ("old", at(1, 20, 10, 0)),
+        ];
+
+        // today_early 由 keep_last 保留，各时间段各保留最新的一份
+        assert_eq!(pruned(&backups, &retention(2, 7, 4, 2), now), ["old"]);
+    }
+
+    #[test]
+    fn keeps_the_latest_raw_snapshot_dirs() {
+        let dir = TestDir::new("backup_raw_snapshots");
+        for day in 1..=7 {
+            let raw_dir = dir.join(format!("lora_pre_restore_202401{:02}_100000_raw", day));
+            fs::create_dir(&raw_dir).unwrap();
+            fs::write(raw_dir.join("apps.json"), "{").unwrap();
+        }
+        fs::create_dir(dir.join("other_raw")).unwrap();
+
+        prune_pre_restore_raw_dirs(&dir);
+
+        let mut remaining: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        remaining.sort();
+        assert_eq!(
+            remaining,
+            [
+                "lora_pre_restore_20240103_100000_raw",
+                "lora_pre_restore_20240104_100000_raw",
+                "lora_pre_restore_20240105_100000_raw",
+                "lora_pre_restore_20240106_100000_raw",
+                "lora_pre_restore_20240107_100000_raw",
+                "other_raw",
+            ]
+        );
+    }
+
+    #[test]
+    fn cleanup_only_removes_auto_backups() {
+        let dir = TestDir::new("backup_cleanup");
+        let files = [
+            "lora_auto_backup_20240101_100000.zip",
+            "lora_auto_backup_20240102_100000.json",
+            "lora_auto_backup_20240103_100000.zip",
+            "lora_pre_restore_20240101_090000.json",
+            "lora_export.json",
+            "notes.txt",
+        ];
+        let base = SystemTime::now() - Duration::from_secs(3600);
+        for (index, name) in files.iter().enumerate() {
+            let file = fs::File::create(dir.join(name)).unwrap();
+            file.set_modified(base + Duration::from_secs(index as u64 * 60))
+                .unwrap();
+        }
+        fs::create_dir(dir.join("lora_pre_restore_20240101_080000_raw")).unwrap();
+
+        BackupManager::cleanup_old_backups(&dir, &retention(1, 0, 0, 0)).unwrap();
+
+        let mut remaining: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        remaining.sort();
+        assert_eq!(
+            remaining,
+            [
+                "lora_auto_backup_20240103_100000.zip",
+                "lora_export.json",
+                "lora_pre_restore_20240101_080000_raw",
+                "lora_pre_restore_20240101_090000.json",
+                "notes.txt",
+            ]
+        );
+    }
 }
@@ -0,0 +1,320 @@
+//! 备份归档模块
+//!
+//! 归档备份是一个 zip 文件，比直接内联 base64 图标的 JSON 备份小得多：
+//! - `manifest.json`：格式版本、Lora 版本、创建时间、主机信息以及各文件的 SHA-256 校验值
+//! - `storage.json`：应用数据，其中的图标替换为对归档内图标文件的引用
+//! - `settings.json`：设置
+//! - `icons/`：从项目数据中提取出来的图标文件
+//!
+//! 读取时会校验所有文件的校验值，并把图标还原为 data URL。
+
+use base64::engine::general_purpose;
+use base64::Engine;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::io::{Cursor, Read, Write};
+use std::path::Path;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::backup::ContentHash;
+use crate::data::{parse_settings_value, parse_storage_value, write_file_atomic};
+use crate::models::{AppData, AppSettings, AppStorage, TrashItem};
+
+/// 归档格式标识
+const ARCHIVE_FORMAT: &str = "lora-backup";
+/// 归档格式版本，归档结构变化时加一
+const ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const STORAGE_FILE: &str = "storage.json";
+const SETTINGS_FILE: &str = "settings.json";
+/// storage.json 中图标引用的前缀，后面是归档内的图标路径
+const ICON_REFERENCE_PREFIX: &str = "lora-archive:";
+
+/// 归档中的数据，已迁移到当前版本
+pub struct ArchiveContents {
+    pub storage: Option<AppStorage>,
+    pub settings: Option<AppSettings>,
+    pub manifest: Value,
+    /// 按迁移前的原始数据计算的内容校验值，归档中同时包含应用数据和设置时才有
+    pub content_hash: Option<ContentHash>,
+}
+
+/// 判断文件内容是否为 zip 归档
+pub fn is_archive(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"PK\x03\x04")
+}
+
+/// 判断文件名是否为归档文件
+pub fn is_archive_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("zip"))
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+fn host_info() -> Value {
+    let hostname = std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok();
+    json!({
+        "hostname": hostname,
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+    })
+}
+
+fn icon_extension(mime: &str) -> &'static str {
+    match mime {
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/svg+xml" => "svg",
+        "image/x-icon" | "image/vnd.microsoft.icon" => "ico",
+        _ => "bin",
+    }
+}
+
+/// 遍历应用数据中所有项目（包括回收站中的项目），`name` 为归档内的唯一名称
+fn for_each_app_mut(storage: &mut AppStorage, mut visit: impl FnMut(&str, &mut AppData)) {
+    for app in &mut storage.apps {
+        visit(&format!("app-{}", app.id), app);
+    }
+    for entry in &mut storage.trash {
+        if let TrashItem::App { app } = &mut entry.item {
+            visit(&format!("trash-{}", entry.id), app);
+        }
+    }
+}
+
+/// 把 data URL 图标提取为归档内的文件，返回 (路径, 内容, MIME 类型)
+fn extract_icons(storage: &mut AppStorage) -> Vec<(String, Vec<u8>, String)> {
+    let mut icons = Vec::new();
+    for_each_app_mut(storage, |name, app| {
+        let Some((mime, data)) = app
+            .icon
+            .strip_prefix("data:")
+            .and_then(|rest| rest.split_once(";base64,"))
+        else {
+            return;
+        };
+        let Ok(bytes) = general_purpose::STANDARD.decode(data) else {
+            return;
+        };
+
+        let path = format!("icons/{}.{}", name, icon_extension(mime));
+        icons.push((path.clone(), bytes, mime.to_string()));
+        app.icon = format!("{}{}", ICON_REFERENCE_PREFIX, path);
+    });
+    icons
+}
+
+/// 写入归档备份
+///
+/// `metadata` 中的字段（例如备份类型、导出范围）会合并到 manifest 中。
+pub fn write_archive(
+    path: &Path,
+    storage: Option<&AppStorage>,
+    settings: Option<&AppSettings>,
+    metadata: Value,
+) -> Result<(), String> {
+    let mut files: Vec<(String, Vec<u8>)> = Vec::new();
+    let mut icon_types = Map::new();
+
+    if let Some(storage) = storage {
+        let mut storage = storage.clone();
+        for (icon_path, bytes, mime) in extract_icons(&mut storage) {
+            icon_types.insert(icon_path.clone(), json!(mime));
+            files.push((icon_path, bytes));
+        }
+        let storage_json =
+            serde_json::to_vec(&storage).map_err(|e| format!("序列化备份数据失败: {}", e))?;
+        files.push((STORAGE_FILE.to_string(), storage_json));
+    }
+    if let Some(settings) = settings {
+        let settings_json =
+            serde_json::to_vec(settings).map_err(|e| format!("序列化备份数据失败: {}", e))?;
+        files.push((SETTINGS_FILE.to_string(), settings_json));
+    }
+
+    let checksums: Map<String, Value> = files
+        .iter()
+        .map(|(name, bytes)| (name.clone(), json!(sha256_hex(bytes))))
+        .collect();
+
+    let mut manifest = json!({
+        "format": ARCHIVE_FORMAT,
+        "format_version": ARCHIVE_FORMAT_VERSION,
+        "version": env!("CARGO_PKG_VERSION"),
+        "created_at": chrono::Local::now().timestamp(),
+        "host": host_info(),
+        "storage_schema_version": storage.map(|storage| storage.schema_version),
+        "settings_schema_version": settings.map(|settings| settings.schema_version),
+        "icons": icon_types,
+        "checksums": checksums,
+    });
+    if let (Some(manifest), Value::Object(metadata)) = (manifest.as_object_mut(), metadata) {
+        manifest.extend(metadata);
+    }
+
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    let manifest_json =
+        serde_json::to_vec_pretty(&manifest).map_err(|e| format!("序列化备份清单失败: {}", e))?;
+    for (name, bytes) in std::iter::once((MANIFEST_FILE.to_string(), manifest_json)).chain(files) {
+        writer
+            .start_file(name.as_str(), options)
+            .and_then(|_| writer.write_all(&bytes).map_err(Into::into))
+            .map_err(|e| format!("写入归档文件 {} 失败: {}", name, e))?;
+    }
+    let archive = writer
+        .finish()
+        .map_err(|e| format!("生成备份归档失败: {}", e))?
+        .into_inner();
+
+    write_file_atomic(path, &archive).map_err(|e| format!("写入备份文件失败: {}", e))
+}
+
+fn read_entry(
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    name: &str,
+) -> Result<Option<Vec<u8>>, String> {
+    let mut file = match archive.by_name(name) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(error) => return Err(format!("读取归档文件 {} 失败: {}", name, error)),
+    };
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)
+        .map_err(|e| format!("读取归档文件 {} 失败: {}", name, e))?;
+    Ok(Some(bytes))
+}
+
+/// 读取归档中的文件并核对 manifest 中记录的校验值
+fn read_verified_entry(
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    checksums: &Map<String, Value>,
+    name: &str,
+) -> Result<Option<Vec<u8>>, String> {
+    let Some(bytes) = read_entry(archive, name)? else {
+        return Ok(None);
+    };
+    match checksums.get(name).and_then(Value::as_str) {
+        Some(expected) if expected == sha256_hex(&bytes) => Ok(Some(bytes)),
+        Some(_) => Err(format!("归档文件 {} 校验失败，备份可能已损坏", name)),
+        None => Err(format!("备份清单中缺少 {} 的校验值", name)),
+    }
+}
+
+/// 把图标引用还原为 data URL
+///
+/// 在迁移之前处理原始 JSON，这样还原出的数据与写入归档前完全一致，
+/// 可以用来核对内容校验值。
+fn restore_icons(
+    storage: &mut Value,
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    manifest: &Value,
+    checksums: &Map<String, Value>,
+) -> Result<(), String> {
+    let icon_types = manifest.get("icons").and_then(Value::as_object);
+    let mut apps: Vec<&mut Value> = Vec::new();
+    for (key, value) in storage.as_object_mut().into_iter().flatten() {
+        let Some(items) = value.as_array_mut() else {
+            continue;
+        };
+        match key.as_str() {
+            "apps" => apps.extend(items.iter_mut()),
+            "trash" => apps.extend(items.iter_mut().filter_map(|entry| entry.get_mut("app"))),
+            _ => {}
+        }
+    }
+
+    for app in apps {
+        let Some(icon) = app.get_mut("icon") else {
+            continue;
+        };
+        let Some(icon_path) = icon
+            .as_str()
+            .and_then(|icon| icon.strip_prefix(ICON_REFERENCE_PREFIX))
+            .map(str::to_string)
+        else {
+            continue;
+        };
+        let mime = icon_types
+            .and_then(|types| types.get(&icon_path))
+            .and_then(Value::as_str)
+            .unwrap_or("image/png");
+        let bytes = read_verified_entry(archive, checksums, &icon_path)?
+            .ok_or_else(|| format!("归档中缺少图标文件 {}", icon_path))?;
+        *icon = json!(format!(
+            "data:{};base64,{}",
+            mime,
+            general_purpose::STANDARD.encode(bytes)
+        ));
+    }
+    Ok(())
+}
+
+/// 读取并校验归档备份
+pub fn read_archive(bytes: &[u8]) -> Result<ArchiveContents, String> {
+    let mut archive =
+        ZipArchive::new(Cursor::new(bytes)).map_err(|e| format!("打开备份归档失败: {}", e))?;
+
+    let manifest_bytes =
+        read_entry(&mut archive, MANIFEST_FILE)?.ok_or("备份归档中缺少清单文件")?;
+    let manifest: Value =
+        serde_json::from_slice(&manifest_bytes).map_err(|e| format!("解析备份清单失败: {}", e))?;
+    if manifest.get("format").and_then(Value::as_str) != Some(ARCHIVE_FORMAT) {
+        return Err("不是 Lora 备份归档".to_string());
+    }
+    let format_version = manifest
+        .get("format_version")
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+    if format_version > u64::from(ARCHIVE_FORMAT_VERSION) {
+        return Err(format!(
+            "备份归档版本 {} 高于当前支持的版本 {}，请升级 Lora",
+            format_version, ARCHIVE_FORMAT_VERSION
+        ));
+    }
+    let checksums = manifest
+        .get("checksums")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+
+    let storage_value = match read_verified_entry(&mut archive, &checksums, STORAGE_FILE)? {
+        Some(storage_bytes) => {
+            let mut value: Value = serde_json::from_slice(&storage_bytes)
+                .map_err(|e| format!("解析备份中的应用数据失败: {}", e))?;
+            restore_icons(&mut value, &mut archive, &manifest, &checksums)?;
+            Some(value)
+        }
+        None => None,
+    };
+
+    let settings_value = match read_verified_entry(&mut archive, &checksums, SETTINGS_FILE)? {
+        Some(settings_bytes) => Some(
+            serde_json::from_slice::<Value>(&settings_bytes)
+                .map_err(|e| format!("解析备份中的设置失败: {}", e))?,
+        ),
+        None => None,
+    };
+
+    let content_hash = match (&storage_value, &settings_value) {
+        (Some(storage), Some(settings)) => Some(ContentHash::of(storage, settings)),
+        _ => None,
+    };
+
+    Ok(ArchiveContents {
+        storage: storage_value.map(parse_storage_value).transpose()?,
+        settings: settings_value.map(parse_settings_value).transpose()?,
+        manifest,
+        content_hash,
+    })
+}
@@ -0,0 +1,212 @@
+//! 备份查看模块
+//!
+//! 在恢复备份之前查看备份的内容：
+//! - 备份概要（备份时间、版本、项目和分组数量等）
+//! - 备份与当前数据之间、或两个备份之间的差异：新增、删除、修改的项目和分组
+//!   （以及修改了哪些字段），以及发生变化的设置项
+
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+use tauri::State;
+
+use crate::backup::{get_backup_dir, load_backup, resolve_backup_path, BackupContents};
+use crate::item_diff::{
+    changed_fields, changed_keys, diff_items, to_object, ItemDiff, APP_USAGE_FIELDS,
+};
+use crate::models::{AppData, AppSettings, AppState, AppStorage, CategoryData};
+
+/// 备份概要
+#[derive(Serialize, Clone, Debug)]
+pub struct BackupSummary {
+    pub name: String,
+    pub size: u64,
+    pub backup_time: Option<i64>,
+    pub backup_type: Option<String>,
+    pub version: Option<String>,
+    pub app_count: usize,
+    pub category_count: usize,
+    pub trash_count: usize,
+}
+
+/// 差异中的一个项目或分组，只包含 id 和名称
+#[derive(Serialize, Clone, Debug)]
+pub struct DiffItem {
+    pub id: String,
+    pub name: String,
+}
+
+/// 被修改的项目或分组，`changed_fields` 为发生变化的字段名
+#[derive(Serialize, Clone, Debug)]
+pub struct ModifiedItem {
+    pub id: String,
+    pub name: String,
+    pub changed_fields: Vec<String>,
+}
+
+/// 发生变化的设置项
+#[derive(Serialize, Clone, Debug)]
+pub struct SettingChange {
+    pub key: String,
+    pub from: Value,
+    pub to: Value,
+}
+
+/// 从 `from` 到 `to` 的差异
+///
+/// `added` 为只在 `to` 中存在的条目，`removed` 为只在 `from` 中存在的条目。
+/// 与当前数据比较时 `from` 为备份、`to` 为当前数据，因此 `removed_apps`
+/// 就是备份之后被删除的项目。
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct BackupDiff {
+    pub added_apps: Vec<DiffItem>,
+    pub removed_apps: Vec<DiffItem>,
+    pub modified_apps: Vec<ModifiedItem>,
+    pub added_categories: Vec<DiffItem>,
+    pub removed_categories: Vec<DiffItem>,
+    pub modified_categories: Vec<ModifiedItem>,
+    pub categories_reordered: bool,
+    pub changed_settings: Vec<SettingChange>,
+}
+
+/// 把列表差异转换为新增、删除和修改的条目，只有 `ignored` 以外的字段变化才算修改
+fn summarize_diff<T: Serialize>(
+    diff: ItemDiff<'_, T>,
+    item: impl Fn(&T) -> DiffItem,
+    ignored: &[&str],
+) -> (Vec<DiffItem>, Vec<DiffItem>, Vec<ModifiedItem>) {
+    let modified = diff
+        .modified
+        .into_iter()
+        .filter_map(|(previous, entry)| {
+            let changed_fields = changed_fields(previous, entry, ignored);
+            if changed_fields.is_empty() {
+                return None;
+            }
+            let DiffItem { id, name } = item(entry);
+            Some(ModifiedItem {
+                id,
+                name,
+                changed_fields,
+            })
+        })
+        .collect();
+
+    (
+        diff.added.into_iter().map(&item).collect(),
+        diff.removed.into_iter().map(&item).collect(),
+        modified,
+    )
+}
+
+fn app_item(app: &AppData) -> DiffItem {
+    DiffItem {
+        id: app.id.to_string(),
+        name: app.name.clone(),
+    }
+}
+
+fn category_item(category: &CategoryData) -> DiffItem {
+    DiffItem {
+        id: category.id.clone(),
+        name: category.name.clone(),
+    }
+}
+
+/// 比较设置时忽略的字段：版本号和自动备份的记录，恢复备份时不会改变这些字段
+const IGNORED_SETTINGS: &[&str] = &[
+    "schema_version",
+    "backup_dir",
+    "last_backup_time",
+    "next_backup_time",
+    "last_backup_hash",
+];
+
+/// 计算两份数据之间的差异
+pub fn diff_data(
+    from_storage: &AppStorage,
+    from_settings: &AppSettings,
+    to_storage: &AppStorage,
+    to_settings: &AppSettings,
+) -> BackupDiff {
+    let (added_apps, removed_apps, modified_apps) = summarize_diff(
+        diff_items(&from_storage.apps, &to_storage.apps),
+        app_item,
+        APP_USAGE_FIELDS,
+    );
+
+    let categories = diff_items(&from_storage.categories, &to_storage.categories);
+    let categories_reordered = categories.reordered;
+    // 分组顺序单独通过 categories_reordered 报告
+    let (added_categories, removed_categories, modified_categories) =
+        summarize_diff(categories, category_item, &["order"]);
+
+    let from_settings = to_object(from_settings);
+    let to_settings = to_object(to_settings);
+    let changed_settings = changed_keys(&from_settings, &to_settings, IGNORED_SETTINGS)
+        .into_iter()
+        .map(|key| SettingChange {
+            from: from_settings.get(&key).cloned().unwrap_or(Value::Null),
+            to: to_settings.get(&key).cloned().unwrap_or(Value::Null),
+            key,
+        })
+        .collect();
+
+    BackupDiff {
+        added_apps,
+        removed_apps,
+        modified_apps,
+        added_categories,
+        removed_categories,
+        modified_categories,
+        categories_reordered,
+        changed_settings,
+    }
+}
+
+fn load_named_backup(backup_dir: &Path, name: &str) -> Result<(BackupContents, u64), String> {
+    let path = resolve_backup_path(backup_dir, name)?;
+    let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    Ok((load_backup(&path)?, size))
+}
+
+/// 查看备份概要
+#[tauri::command]
+pub fn inspect_backup(state: State<'_, AppState>, name: String) -> Result<BackupSummary, String> {
+    let (backup, size) = load_named_backup(&get_backup_dir(&state.store)?, &name)?;
+    Ok(BackupSummary {
+        name,
+        size,
+        backup_time: backup.backup_time,
+        backup_type: backup.backup_type,
+        version: backup.version,
+        app_count: backup.storage.apps.len(),
+        category_count: backup.storage.categories.len(),
+        trash_count: backup.storage.trash.len(),
+    })
+}
+
+/// 比较备份与当前数据，或指定 `compare_to` 时比较两个备份
+#[tauri::command]
+pub fn diff_backup(
+    state: State<'_, AppState>,
+    name: String,
+    compare_to: Option<String>,
+) -> Result<BackupDiff, String> {
+    let backup_dir = get_backup_dir(&state.store)?;
+    let (from, _) = load_named_backup(&backup_dir, &name)?;
+    let (to_storage, to_settings) = match compare_to {
+        Some(other) => {
+            let (to, _) = load_named_backup(&backup_dir, &other)?;
+            (to.storage, to.settings)
+        }
+        None => (state.store.storage()?, state.store.settings()?),
+    };
+
+    Ok(diff_data(
+        &from.storage,
+        &from.settings,
+        &to_storage,
+        &to_settings,
+    ))
+}
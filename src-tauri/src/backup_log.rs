@@ -0,0 +1,211 @@
+//! 备份记录模块
+//!
+//! 每次自动备份、修改后备份和手动备份都会：
+//! - 在开始时广播 `backup-started` 事件，结束时广播 `backup-succeeded` 或
+//!   `backup-failed` 事件
+//! - 把结果（包括失败原因）写入数据目录中的 backup_log.json，只保留最近的记录
+//!
+//! 备份状态根据记录计算，连续失败（例如磁盘已满、没有写入权限）时可以在界面上看到。
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, PoisonError};
+use tauri::{AppHandle, Emitter};
+
+use crate::data::{get_app_data_dir, write_file_atomic};
+
+/// 备份开始事件名
+pub const BACKUP_STARTED_EVENT: &str = "backup-started";
+/// 备份成功（或数据没有变化而跳过）事件名
+pub const BACKUP_SUCCEEDED_EVENT: &str = "backup-succeeded";
+/// 备份失败事件名
+pub const BACKUP_FAILED_EVENT: &str = "backup-failed";
+
+/// 最多保留的备份记录数量
+const MAX_LOG_ENTRIES: usize = 100;
+
+/// 保证多个备份任务同时写入记录时不会互相覆盖
+static LOG_LOCK: Mutex<()> = Mutex::new(());
+
+/// 触发备份的方式
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupTrigger {
+    /// 按备份间隔定时执行
+    Scheduled,
+    /// 应用数据修改后执行
+    Change,
+    /// 用户手动执行
+    Manual,
+}
+
+/// 备份结果
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupOutcome {
+    Succeeded,
+    /// 数据自上次备份以来没有变化，没有写入新的备份
+    Skipped,
+    Failed,
+}
+
+/// 备份开始事件内容
+#[derive(Serialize, Clone, Debug)]
+pub struct BackupStarted {
+    pub trigger: BackupTrigger,
+    pub started_at: i64,
+}
+
+/// 一次备份的记录，也是备份结束事件的内容
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BackupAttempt {
+    pub trigger: BackupTrigger,
+    pub started_at: i64,
+    pub finished_at: i64,
+    pub outcome: BackupOutcome,
+    pub message: Option<String>,
+    pub error: Option<String>,
+}
+
+/// 根据备份记录计算的备份状态
+#[derive(Serialize, Clone, Debug)]
+pub struct BackupRunStatus {
+    /// "ok"：最近一次备份成功；"failing"：最近一次备份失败；"unknown"：没有记录
+    /// （跳过的备份不计入）
+    pub state: String,
+    pub last_attempt: Option<BackupAttempt>,
+    pub last_success_at: Option<i64>,
+    pub last_failure_at: Option<i64>,
+    /// 最近一次失败的原因
+    pub last_error: Option<String>,
+    /// 最近连续失败的次数
+    pub consecutive_failures: usize,
+}
+
+fn log_file_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("backup_log.json"))
+}
+
+/// 读取备份记录，最新的在前；文件不存在或无法解析时返回空列表
+pub fn read_backup_log() -> Vec<BackupAttempt> {
+    log_file_path()
+        .ok()
+        .and_then(|path| fs::read(path).ok())
+        .and_then(|json_data| serde_json::from_slice(&json_data).ok())
+        .unwrap_or_default()
+}
+
+fn write_backup_log(log: &[BackupAttempt]) -> Result<(), String> {
+    let json_data =
+        serde_json::to_string_pretty(log).map_err(|e| format!("序列化备份记录失败: {}", e))?;
+    write_file_atomic(&log_file_path()?, json_data.as_bytes())
+        .map_err(|e| format!("保存备份记录失败: {}", e))
+}
+
+fn append_attempt(attempt: BackupAttempt) -> Result<(), String> {
+    let _guard = LOG_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut log = read_backup_log();
+    log.insert(0, attempt);
+    log.truncate(MAX_LOG_ENTRIES);
+    write_backup_log(&log)
+}
+
+/// 根据备份记录计算备份状态
+///
+/// 数据没有变化而跳过的备份不影响成功或失败的判断，失败之后的跳过不会掩盖失败。
+pub fn backup_run_status() -> BackupRunStatus {
+    let log = read_backup_log();
+    let attempts: Vec<&BackupAttempt> = log
+        .iter()
+        .filter(|attempt| attempt.outcome != BackupOutcome::Skipped)
+        .collect();
+    let is_failure = |attempt: &&&BackupAttempt| attempt.outcome == BackupOutcome::Failed;
+    let last_failure = attempts.iter().find(is_failure);
+
+    BackupRunStatus {
+        state: match attempts.first() {
+            Some(attempt) if attempt.outcome == BackupOutcome::Failed => "failing",
+            Some(_) => "ok",
+            None => "unknown",
+        }
+        .to_string(),
+        last_success_at: attempts
+            .iter()
+            .find(|attempt| attempt.outcome == BackupOutcome::Succeeded)
+            .map(|attempt| attempt.finished_at),
+        last_failure_at: last_failure.map(|attempt| attempt.finished_at),
+        last_error: last_failure.and_then(|attempt| attempt.error.clone()),
+        consecutive_failures: attempts.iter().take_while(is_failure).count(),
+        last_attempt: log.first().cloned(),
+    }
+}
+
+/// 广播备份开始事件，返回开始时间
+pub fn backup_started(app: &AppHandle, trigger: BackupTrigger) -> i64 {
+    let started_at = chrono::Local::now().timestamp();
+    if let Err(error) = app.emit(
+        BACKUP_STARTED_EVENT,
+        BackupStarted {
+            trigger,
+            started_at,
+        },
+    ) {
+        eprintln!("广播备份开始事件失败: {}", error);
+    }
+    started_at
+}
+
+/// 记录备份结果并广播结束事件
+///
+/// `result` 为 `Ok(None)` 表示数据没有变化而跳过了备份。
+pub fn backup_finished(
+    app: &AppHandle,
+    trigger: BackupTrigger,
+    started_at: i64,
+    result: &Result<Option<String>, String>,
+) {
+    let (outcome, message, error) = match result {
+        Ok(Some(message)) => (BackupOutcome::Succeeded, Some(message.clone()), None),
+        Ok(None) => (BackupOutcome::Skipped, None, None),
+        Err(error) => (BackupOutcome::Failed, None, Some(error.clone())),
+    };
+    let attempt = BackupAttempt {
+        trigger,
+        started_at,
+        finished_at: chrono::Local::now().timestamp(),
+        outcome,
+        message,
+        error,
+    };
+
+    let event = if outcome == BackupOutcome::Failed {
+        BACKUP_FAILED_EVENT
+    } else {
+        BACKUP_SUCCEEDED_EVENT
+    };
+    if let Err(error) = app.emit(event, &attempt) {
+        eprintln!("广播备份结果事件失败: {}", error);
+    }
+    if let Err(error) = append_attempt(attempt) {
+        eprintln!("{}", error);
+    }
+}
+
+/// 获取最近的备份记录，最新的在前
+#[tauri::command]
+pub fn get_backup_log(limit: Option<usize>) -> Vec<BackupAttempt> {
+    let mut log = read_backup_log();
+    if let Some(limit) = limit {
+        log.truncate(limit);
+    }
+    log
+}
+
+/// 清空备份记录
+#[tauri::command]
+pub fn clear_backup_log() -> Result<String, String> {
+    let _guard = LOG_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    write_backup_log(&[])?;
+    Ok("备份记录已清空".to_string())
+}
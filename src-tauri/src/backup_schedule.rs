@@ -0,0 +1,478 @@
+//! 备份计划模块
+//!
+//! 自动备份间隔（设置中的 `backup_interval`）支持三种写法：
+//! - 预设：`hourly`、`daily`、`weekly`、`monthly`，按本地时间在当前时刻的基础上
+//!   加一小时、一天、七天或一个月（下个月没有这一天时取月末）
+//! - 固定间隔：`every 6h`、`90m`、`1d 12h`，单位为 m、h、d、w，最短 5 分钟
+//! - 类 cron 表达式：`分 时 日 月 周`，例如 `0 3 * * *` 表示每天 3:00，
+//!   `30 2 * * mon-fri` 表示工作日 2:30；支持 `*`、列表、范围、步长，
+//!   日字段可以用 `L` 表示月末
+//!
+//! 按本地日历计算的时间会处理夏令时：跳过的时刻顺延到切换之后的第一分钟，
+//! 重复的时刻取第一次出现的时间。
+
+use chrono::{
+    DateTime, Datelike, Duration, Local, LocalResult, Months, NaiveDate, NaiveDateTime, TimeZone,
+    Timelike,
+};
+
+/// 固定间隔的最小值（分钟）
+const MIN_INTERVAL_MINUTES: i64 = 5;
+/// 固定间隔的最大值（天）
+const MAX_INTERVAL_DAYS: i64 = 366;
+/// 查找 cron 表达式下一次触发时间的最大范围（天），覆盖一个完整的闰年周期
+const CRON_SEARCH_DAYS: i64 = 366 * 4 + 1;
+
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// 解析后的备份计划
+#[derive(Debug, Clone, PartialEq)]
+pub enum BackupSchedule {
+    /// 固定时长的间隔，与时区无关
+    Interval(Duration),
+    /// 按本地日历前进若干月和天，时刻保持不变
+    Calendar { months: u32, days: u32 },
+    /// 类 cron 表达式
+    Cron(CronSchedule),
+}
+
+/// 类 cron 表达式，各字段以位掩码保存允许的取值
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    last_day_of_month: bool,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+impl BackupSchedule {
+    /// 解析备份间隔设置
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expression = expression.trim().to_lowercase();
+        match expression.as_str() {
+            "" => return Err("备份间隔不能为空".to_string()),
+            "hourly" => return Ok(BackupSchedule::Interval(Duration::hours(1))),
+            "daily" => return Ok(BackupSchedule::Calendar { months: 0, days: 1 }),
+            "weekly" => return Ok(BackupSchedule::Calendar { months: 0, days: 7 }),
+            "monthly" => return Ok(BackupSchedule::Calendar { months: 1, days: 0 }),
+            _ => {}
+        }
+
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() == 5 && !expression.starts_with("every") {
+            return CronSchedule::parse(&fields).map(BackupSchedule::Cron);
+        }
+        parse_interval(&expression).map(BackupSchedule::Interval)
+    }
+
+    /// 计算 `now` 之后的下一次备份时间，日历计算使用 `now` 所在的时区
+    pub fn next_after<Tz: TimeZone>(&self, now: DateTime<Tz>) -> Result<DateTime<Tz>, String> {
+        match self {
+            BackupSchedule::Interval(interval) => Ok(now + *interval),
+            BackupSchedule::Calendar { months, days } => {
+                let naive = now
+                    .naive_local()
+                    .checked_add_months(Months::new(*months))
+                    .and_then(|naive| naive.checked_add_signed(Duration::days(*days as i64)))
+                    .ok_or("计算下次备份时间失败")?;
+                resolve_local(&now.timezone(), naive).ok_or_else(|| "构建本地时间失败".to_string())
+            }
+            BackupSchedule::Cron(cron) => cron
+                .next_after(now)
+                .ok_or_else(|| "计划表达式不会再触发".to_string()),
+        }
+    }
+}
+
+/// 把本地日历时间转换为实际时间
+///
+/// 夏令时开始时被跳过的时刻顺延到切换后的第一分钟；结束时重复的时刻取第一次。
+fn resolve_local<Tz: TimeZone>(timezone: &Tz, naive: NaiveDateTime) -> Option<DateTime<Tz>> {
+    let mut candidate = naive;
+    // 时区切换造成的空档不会超过一天
+    for _ in 0..=24 * 60 {
+        match timezone.from_local_datetime(&candidate) {
+            LocalResult::Single(time) => return Some(time),
+            // 两个候选的先后顺序因平台而异，取较早的一个
+            LocalResult::Ambiguous(first, second) => return Some(first.min(second)),
+            LocalResult::None => {
+                candidate = (candidate + Duration::minutes(1)).with_second(0)?;
+            }
+        }
+    }
+    None
+}
+
+/// 解析固定间隔，例如 `every 6h`、`90m`、`1d 12h`
+fn parse_interval(expression: &str) -> Result<Duration, String> {
+    let invalid = || format!("无法识别的备份间隔: {}", expression);
+    let body = expression
+        .strip_prefix("every")
+        .unwrap_or(expression)
+        .replace(' ', "");
+    if body.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    for ch in body.chars() {
+        if ch.is_ascii_digit() {
+            number.push(ch);
+            continue;
+        }
+        let value: i64 = number.parse().map_err(|_| invalid())?;
+        number.clear();
+        let part = match ch {
+            'm' => Duration::try_minutes(value),
+            'h' => Duration::try_hours(value),
+            'd' => Duration::try_days(value),
+            'w' => Duration::try_weeks(value),
+            _ => None,
+        }
+        .ok_or_else(invalid)?;
+        total = total.checked_add(&part).ok_or_else(invalid)?;
+    }
+    if !number.is_empty() {
+        return Err(format!("备份间隔缺少单位(m、h、d、w): {}", expression));
+    }
+
+    if total < Duration::minutes(MIN_INTERVAL_MINUTES) {
+        return Err(format!("备份间隔不能短于 {} 分钟", MIN_INTERVAL_MINUTES));
+    }
+    if total > Duration::days(MAX_INTERVAL_DAYS) {
+        return Err(format!("备份间隔不能超过 {} 天", MAX_INTERVAL_DAYS));
+    }
+    Ok(total)
+}
+
+/// 解析 cron 字段中的单个值，`names` 为可用的英文缩写，从 `name_base` 开始编号
+fn parse_cron_value(value: &str, names: &[&str], name_base: u32) -> Option<u32> {
+    value.parse().ok().or_else(|| {
+        names
+            .iter()
+            .position(|name| *name == value)
+            .map(|index| index as u32 + name_base)
+    })
+}
+
+/// 解析 cron 字段为位掩码，取值范围为 `min..=max`
+fn parse_cron_field(
+    field: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+    name_base: u32,
+) -> Option<u64> {
+    let mut mask = 0u64;
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().ok().filter(|s| *s > 0)?),
+            None => (item, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (
+                parse_cron_value(start, names, name_base)?,
+                parse_cron_value(end, names, name_base)?,
+            )
+        } else {
+            let start = parse_cron_value(range, names, name_base)?;
+            // `5/15` 表示从 5 开始每 15 一次
+            (start, if item.contains('/') { max } else { start })
+        };
+        if start < min || end > max || start > end {
+            return None;
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Some(mask)
+}
+
+fn has_bit(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+fn is_last_day_of_month(date: NaiveDate) -> bool {
+    date.succ_opt()
+        .is_some_and(|next| next.month() != date.month())
+}
+
+impl CronSchedule {
+    fn parse(fields: &[&str]) -> Result<Self, String> {
+        let invalid = |name: &str, field: &str| format!("cron 表达式的{}字段无效: {}", name, field);
+        let [minute, hour, day_of_month, month, day_of_week] = fields else {
+            return Err("cron 表达式需要 5 个字段: 分 时 日 月 周".to_string());
+        };
+
+        let minutes =
+            parse_cron_field(minute, 0, 59, &[], 0).ok_or_else(|| invalid("分", minute))?;
+        let hours = parse_cron_field(hour, 0, 23, &[], 0).ok_or_else(|| invalid("时", hour))?;
+        let months =
+            parse_cron_field(month, 1, 12, &MONTH_NAMES, 1).ok_or_else(|| invalid("月", month))?;
+
+        let mut last_day_of_month = false;
+        let day_items: Vec<&str> = day_of_month
+            .split(',')
+            .filter(|item| {
+                let is_last = *item == "l";
+                last_day_of_month |= is_last;
+                !is_last
+            })
+            .collect();
+        let days_of_month = if day_items.is_empty() {
+            0
+        } else {
+            parse_cron_field(&day_items.join(","), 1, 31, &[], 0)
+                .ok_or_else(|| invalid("日", day_of_month))?
+        };
+
+        let mut days_of_week = parse_cron_field(day_of_week, 0, 7, &WEEKDAY_NAMES, 0)
+            .ok_or_else(|| invalid("周", day_of_week))?;
+        // 0 和 7 都表示周日
+        if has_bit(days_of_week, 7) {
+            days_of_week |= 1;
+        }
+
+        let cron = CronSchedule {
+            minutes,
+            hours,
+            days_of_month,
+            months,
+            days_of_week,
+            last_day_of_month,
+            day_of_month_restricted: *day_of_month != "*",
+            day_of_week_restricted: *day_of_week != "*",
+        };
+        if cron.next_after(Local::now()).is_none() {
+            return Err(format!("cron 表达式永远不会触发: {}", fields.join(" ")));
+        }
+        Ok(cron)
+    }
+
+    /// 日期是否满足日、月、周字段；日和周都有限制时满足其一即可（与 cron 一致）
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if !has_bit(self.months, date.month()) {
+            return false;
+        }
+        let day_of_month = has_bit(self.days_of_month, date.day())
+            || (self.last_day_of_month && is_last_day_of_month(date));
+        let day_of_week = has_bit(self.days_of_week, date.weekday().num_days_from_sunday());
+        match (self.day_of_month_restricted, self.day_of_week_restricted) {
+            (true, true) => day_of_month || day_of_week,
+            (true, false) => day_of_month,
+            (false, true) => day_of_week,
+            (false, false) => true,
+        }
+    }
+
+    /// 计算 `now` 之后的下一次触发时间
+    fn next_after<Tz: TimeZone>(&self, now: DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let timezone = now.timezone();
+        let today = now.date_naive();
+        for day_offset in 0..CRON_SEARCH_DAYS {
+            let date = today + Duration::days(day_offset);
+            if !self.matches_date(date) {
+                continue;
+            }
+            for hour in (0..24).filter(|hour| has_bit(self.hours, *hour)) {
+                for minute in (0..60).filter(|minute| has_bit(self.minutes, *minute)) {
+                    let naive = date.and_hms_opt(hour, minute, 0)?;
+                    if day_offset == 0 && naive <= now.naive_local() {
+                        continue;
+                    }
+                    match resolve_local(&timezone, naive) {
+                        Some(time) if time > now => return Some(time),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, Weekday};
+
+    /// 美国东部时区的规则：3 月第二个周日 2:00 跳到 3:00，11 月第一个周日 2:00 回到 1:00
+    ///
+    /// 测试使用固定的时区，不依赖也不修改进程的 `TZ` 环境变量。
+    #[derive(Debug, Clone, Copy)]
+    struct Eastern;
+
+    impl Eastern {
+        fn standard() -> FixedOffset {
+            FixedOffset::west_opt(5 * 3600).unwrap()
+        }
+
+        fn daylight() -> FixedOffset {
+            FixedOffset::west_opt(4 * 3600).unwrap()
+        }
+
+        /// 按 UTC 时间判断是否处于夏令时
+        fn offset_at(utc: &NaiveDateTime) -> FixedOffset {
+            let sunday = |month, n| {
+                NaiveDate::from_weekday_of_month_opt(utc.year(), month, Weekday::Sun, n).unwrap()
+            };
+            let start = sunday(3, 2).and_hms_opt(7, 0, 0).unwrap();
+            let end = sunday(11, 1).and_hms_opt(6, 0, 0).unwrap();
+            if (start..end).contains(utc) {
+                Self::daylight()
+            } else {
+                Self::standard()
+            }
+        }
+    }
+
+    impl TimeZone for Eastern {
+        type Offset = FixedOffset;
+
+        fn from_offset(_offset: &FixedOffset) -> Self {
+            Eastern
+        }
+
+        fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+            self.offset_from_local_datetime(&local.and_hms_opt(0, 0, 0).unwrap())
+        }
+
+        fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+            let valid: Vec<FixedOffset> = [Self::standard(), Self::daylight()]
+                .into_iter()
+                .filter(|offset| {
+                    let utc = *local - Duration::seconds(offset.local_minus_utc().into());
+                    Self::offset_at(&utc) == *offset
+                })
+                .collect();
+            match valid[..] {
+                [offset] => LocalResult::Single(offset),
+                [first, second] => LocalResult::Ambiguous(first, second),
+                _ => LocalResult::None,
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            self.offset_from_utc_datetime(&utc.and_hms_opt(0, 0, 0).unwrap())
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+            Self::offset_at(utc)
+        }
+    }
+
+    fn naive(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .and_then(|date| date.and_hms_opt(hour, minute, 0))
+            .unwrap()
+    }
+
+    fn local(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Eastern> {
+        resolve_local(&Eastern, naive(year, month, day, hour, minute)).unwrap()
+    }
+
+    #[test]
+    fn skipped_local_time_moves_past_the_gap() {
+        let time = resolve_local(&Eastern, naive(2024, 3, 10, 2, 30)).unwrap();
+        assert_eq!(time.naive_local(), naive(2024, 3, 10, 3, 0));
+        assert_eq!(time.offset().local_minus_utc(), -4 * 3600);
+    }
+
+    #[test]
+    fn repeated_local_time_uses_first_occurrence() {
+        let time = resolve_local(&Eastern, naive(2024, 11, 3, 1, 30)).unwrap();
+        assert_eq!(time.naive_local(), naive(2024, 11, 3, 1, 30));
+        assert_eq!(time.offset().local_minus_utc(), -4 * 3600);
+    }
+
+    #[test]
+    fn daily_schedule_keeps_wall_clock_time_across_dst() {
+        let schedule = BackupSchedule::parse("daily").unwrap();
+        let next = schedule.next_after(local(2024, 3, 9, 2, 30)).unwrap();
+        assert_eq!(next.naive_local(), naive(2024, 3, 10, 3, 0));
+    }
+
+    #[test]
+    fn monthly_schedule_clamps_to_end_of_month() {
+        let schedule = BackupSchedule::Calendar { months: 1, days: 0 };
+        let next = schedule.next_after(local(2024, 1, 31, 10, 0)).unwrap();
+        assert_eq!(next.naive_local(), naive(2024, 2, 29, 10, 0));
+        let next = schedule.next_after(local(2023, 1, 31, 10, 0)).unwrap();
+        assert_eq!(next.naive_local(), naive(2023, 2, 28, 10, 0));
+    }
+
+    #[test]
+    fn cron_last_day_of_month() {
+        let schedule = BackupSchedule::parse("0 3 L * *").unwrap();
+        let next = schedule.next_after(local(2024, 2, 10, 12, 0)).unwrap();
+        assert_eq!(next.naive_local(), naive(2024, 2, 29, 3, 0));
+        let next = schedule.next_after(next).unwrap();
+        assert_eq!(next.naive_local(), naive(2024, 3, 31, 3, 0));
+    }
+
+    #[test]
+    fn cron_with_day_of_week() {
+        let schedule = BackupSchedule::parse("30 2 * * mon-fri").unwrap();
+        // 2024-03-09 是周六
+        let next = schedule.next_after(local(2024, 3, 9, 12, 0)).unwrap();
+        assert_eq!(next.naive_local(), naive(2024, 3, 11, 2, 30));
+    }
+
+    #[test]
+    fn parses_presets_and_intervals() {
+        assert_eq!(
+            BackupSchedule::parse("hourly"),
+            Ok(BackupSchedule::Interval(Duration::hours(1)))
+        );
+        assert_eq!(
+            BackupSchedule::parse(" Weekly "),
+            Ok(BackupSchedule::Calendar { months: 0, days: 7 })
+        );
+        assert_eq!(
+            BackupSchedule::parse("every 1d 12h"),
+            Ok(BackupSchedule::Interval(Duration::hours(36)))
+        );
+        assert_eq!(
+            BackupSchedule::parse("90m"),
+            Ok(BackupSchedule::Interval(Duration::minutes(90)))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        for expression in [
+            "",
+            "every",
+            "6",
+            "6x",
+            "1m",
+            "400d",
+            "* * *",
+            "60 * * * *",
+            "0 24 * * *",
+            "0 0 0 * *",
+            "0 0 * 13 *",
+            "0 0 * * 8",
+            "*/0 * * * *",
+            "0 0 5-1 * *",
+            "0 0 30 feb *",
+        ] {
+            assert!(
+                BackupSchedule::parse(expression).is_err(),
+                "应当拒绝: {:?}",
+                expression
+            );
+        }
+    }
+}
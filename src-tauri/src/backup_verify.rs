@@ -0,0 +1,108 @@
+//! 备份校验模块
+//!
+//! 没有被读取过的备份不一定能用。校验会重新读取备份目录中的每个备份文件
+//! （自动备份和恢复前快照），解析为当前版本的应用数据和设置，并核对备份中
+//! 记录的内容校验值。最近一次校验结果保存在数据目录中，供备份状态显示。
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::State;
+
+use crate::backup::{get_backup_dir, list_backup_files, list_pre_restore_snapshots, load_backup};
+use crate::data::{get_app_data_dir, write_file_atomic};
+use crate::models::AppState;
+
+/// 单个备份文件的校验结果
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupHealth {
+    /// 可以解析，内容校验值一致
+    Ok,
+    /// 可以解析，但备份没有记录内容校验值（旧版本创建的备份）
+    Unverified,
+    /// 无法读取、无法解析或校验值不一致
+    Corrupt,
+}
+
+/// 单个备份文件的校验结果
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BackupCheck {
+    pub name: String,
+    pub size: u64,
+    pub health: BackupHealth,
+    pub error: Option<String>,
+}
+
+/// 一次校验的结果
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BackupVerification {
+    pub verified_at: i64,
+    pub backup_dir: String,
+    pub ok_count: usize,
+    pub unverified_count: usize,
+    pub corrupt_count: usize,
+    pub files: Vec<BackupCheck>,
+}
+
+fn verification_file_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("backup_verification.json"))
+}
+
+/// 读取最近一次校验结果，没有校验过或文件无法解析时返回 None
+pub fn load_last_verification() -> Option<BackupVerification> {
+    let json_data = fs::read(verification_file_path().ok()?).ok()?;
+    serde_json::from_slice(&json_data).ok()
+}
+
+fn check_backup(path: PathBuf) -> BackupCheck {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+
+    let (health, error) = match load_backup(&path) {
+        Ok(backup) if backup.content_hash.is_some() => (BackupHealth::Ok, None),
+        Ok(_) => (BackupHealth::Unverified, None),
+        Err(error) => (BackupHealth::Corrupt, Some(error)),
+    };
+    BackupCheck {
+        name,
+        size,
+        health,
+        error,
+    }
+}
+
+/// 校验备份目录中的所有备份文件，并保存校验结果
+#[tauri::command]
+pub async fn verify_backups(state: State<'_, AppState>) -> Result<BackupVerification, String> {
+    let backup_dir = get_backup_dir(&state.store)?;
+    if !backup_dir.is_dir() {
+        return Err(format!("备份目录不存在: {}", backup_dir.display()));
+    }
+
+    let files: Vec<BackupCheck> = list_backup_files(&backup_dir)
+        .into_iter()
+        .chain(list_pre_restore_snapshots(&backup_dir))
+        .map(|(path, _)| check_backup(path))
+        .collect();
+    let count = |health: BackupHealth| files.iter().filter(|file| file.health == health).count();
+
+    let verification = BackupVerification {
+        verified_at: chrono::Local::now().timestamp(),
+        backup_dir: backup_dir.to_string_lossy().to_string(),
+        ok_count: count(BackupHealth::Ok),
+        unverified_count: count(BackupHealth::Unverified),
+        corrupt_count: count(BackupHealth::Corrupt),
+        files,
+    };
+
+    let json_data = serde_json::to_string_pretty(&verification)
+        .map_err(|e| format!("序列化校验结果失败: {}", e))?;
+    write_file_atomic(&verification_file_path()?, json_data.as_bytes())
+        .map_err(|e| format!("保存校验结果失败: {}", e))?;
+
+    Ok(verification)
+}
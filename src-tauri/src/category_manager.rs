@@ -0,0 +1,613 @@
+//! 分组管理模块
+//!
+//! 此模块负责分组的增删改查，并维护分组与项目之间的引用关系：
+//! - 新建、重命名、修改图标、调整顺序
+//! - 删除分组时把其中的项目移动到其他分组，或只移除该分组的归属
+//! - 调整单个分组内项目的顺序，属于多个分组的项目在每个分组中分别排序
+//! - 保证分组 id 唯一，且 `AppData.category` 始终等于 `category_ids` 的第一个分组
+
+use std::collections::{BTreeMap, HashSet};
+use tauri::State;
+
+use crate::data::normalize_app_categories;
+use crate::models::{AppData, AppState, AppStorage, CategoryData, TrashItem};
+use crate::trash::{move_to_trash, trash_retention_days};
+
+/// "全部应用"分组的 id，该分组始终存在且固定排在第一位
+const ALL_CATEGORY_ID: &str = "all";
+const DEFAULT_CATEGORY_ICON: &str = "icon-apps";
+
+/// 校验分组 id 非空且互不重复
+pub fn validate_category_ids(categories: &[CategoryData]) -> Result<(), String> {
+    let mut seen_ids = HashSet::new();
+    for category in categories {
+        if category.id.trim().is_empty() {
+            return Err("分组 id 不能为空".to_string());
+        }
+        if !seen_ids.insert(category.id.as_str()) {
+            return Err(format!("分组 id 重复: {}", category.id));
+        }
+    }
+    Ok(())
+}
+
+fn normalize_category_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("分组名称不能为空".to_string());
+    }
+    Ok(name.to_string())
+}
+
+fn find_category_mut<'a>(
+    storage: &'a mut AppStorage,
+    category_id: &str,
+) -> Result<&'a mut CategoryData, String> {
+    storage
+        .categories
+        .iter_mut()
+        .find(|category| category.id == category_id)
+        .ok_or_else(|| format!("分组不存在: {}", category_id))
+}
+
+/// 生成新的分组 id，与前端一致使用毫秒时间戳，冲突时顺延
+pub fn generate_category_id(storage: &AppStorage) -> String {
+    let mut timestamp = chrono::Utc::now().timestamp_millis();
+    loop {
+        let id = timestamp.to_string();
+        if !storage.categories.iter().any(|category| category.id == id) {
+            return id;
+        }
+        timestamp += 1;
+    }
+}
+
+/// 按当前列表顺序重新分配分组排序值，"全部应用"固定为 0
+fn renumber_categories(categories: &mut [CategoryData]) {
+    let mut next_order = 1;
+    for category in categories {
+        if category.id == ALL_CATEGORY_ID {
+            category.order = Some(0);
+        } else {
+            category.order = Some(next_order);
+            next_order += 1;
+        }
+    }
+}
+
+/// 在应用数据中新建分组，未指定 id 时自动生成
+fn add_category(
+    storage: &mut AppStorage,
+    name: &str,
+    icon: Option<String>,
+    id: Option<String>,
+) -> Result<CategoryData, String> {
+    let name = normalize_category_name(name)?;
+    let icon = icon
+        .map(|icon| icon.trim().to_string())
+        .filter(|icon| !icon.is_empty())
+        .unwrap_or_else(|| DEFAULT_CATEGORY_ICON.to_string());
+
+    let id = match id.map(|id| id.trim().to_string()) {
+        Some(id) if id.is_empty() => return Err("分组 id 不能为空".to_string()),
+        Some(id) => {
+            if storage.categories.iter().any(|category| category.id == id) {
+                return Err(format!("分组 id 已存在: {}", id));
+            }
+            id
+        }
+        None => generate_category_id(storage),
+    };
+
+    let next_order = storage
+        .categories
+        .iter()
+        .filter_map(|category| category.order)
+        .max()
+        .map_or(1, |order| order + 1);
+
+    let category = CategoryData {
+        id,
+        name,
+        icon,
+        is_default: false,
+        order: Some(next_order),
+        hidden: false,
+    };
+    storage.categories.push(category.clone());
+    Ok(category)
+}
+
+/// 新建分组，未指定 id 时自动生成
+#[tauri::command]
+pub fn create_category(
+    state: State<'_, AppState>,
+    name: String,
+    icon: Option<String>,
+    id: Option<String>,
+) -> Result<CategoryData, String> {
+    state.store.update_library("新建分组", |storage| {
+        add_category(storage, &name, icon, id)
+    })
+}
+
+/// 重命名分组
+#[tauri::command]
+pub fn rename_category(
+    state: State<'_, AppState>,
+    category_id: String,
+    name: String,
+) -> Result<String, String> {
+    let name = normalize_category_name(&name)?;
+    state.store.update_library("重命名分组", |storage| {
+        find_category_mut(storage, &category_id)?.name = name;
+        Ok(())
+    })?;
+    Ok("分组名称已更新".to_string())
+}
+
+/// 修改分组图标
+#[tauri::command]
+pub fn update_category_icon(
+    state: State<'_, AppState>,
+    category_id: String,
+    icon: String,
+) -> Result<String, String> {
+    let icon = icon.trim().to_string();
+    if icon.is_empty() {
+        return Err("分组图标不能为空".to_string());
+    }
+
+    state.store.update_library("修改分组图标", |storage| {
+        find_category_mut(storage, &category_id)?.icon = icon;
+        Ok(())
+    })?;
+    Ok("分组图标已更新".to_string())
+}
+
+/// 按给定的 id 顺序重排分组
+///
+/// 列表必须包含除"全部应用"以外的所有分组且不能重复，"全部应用"始终排在第一位。
+fn apply_category_order(storage: &mut AppStorage, category_ids: &[String]) -> Result<(), String> {
+    let requested_ids: Vec<&str> = category_ids
+        .iter()
+        .map(String::as_str)
+        .filter(|id| *id != ALL_CATEGORY_ID)
+        .collect();
+
+    let mut seen_ids = HashSet::new();
+    for id in &requested_ids {
+        if !seen_ids.insert(*id) {
+            return Err(format!("分组 id 重复: {}", id));
+        }
+        if !storage.categories.iter().any(|category| category.id == *id) {
+            return Err(format!("分组不存在: {}", id));
+        }
+    }
+    if let Some(missing) = storage
+        .categories
+        .iter()
+        .find(|category| category.id != ALL_CATEGORY_ID && !seen_ids.contains(category.id.as_str()))
+    {
+        return Err(format!("排序列表缺少分组: {}", missing.id));
+    }
+
+    let mut categories = std::mem::take(&mut storage.categories);
+    categories.sort_by_key(|category| {
+        requested_ids
+            .iter()
+            .position(|id| *id == category.id)
+            .map_or(0, |position| position + 1)
+    });
+    renumber_categories(&mut categories);
+    storage.categories = categories;
+    Ok(())
+}
+
+/// 按给定的 id 顺序重排分组
+#[tauri::command]
+pub fn reorder_categories(
+    state: State<'_, AppState>,
+    category_ids: Vec<String>,
+) -> Result<String, String> {
+    state.store.update_library("调整分组顺序", |storage| {
+        apply_category_order(storage, &category_ids)
+    })?;
+    Ok("分组顺序已更新".to_string())
+}
+
+/// 获取项目在指定分组中的排序值，"全部应用"使用项目的 order
+pub fn app_order_in_category(app: &AppData, category_id: &str) -> Option<i32> {
+    if category_id == ALL_CATEGORY_ID {
+        app.order
+    } else {
+        app.category_orders.get(category_id).copied()
+    }
+}
+
+/// 按给定的项目 id 顺序重排单个分组内的项目
+///
+/// 只影响该分组中的排序，项目在其他分组中的位置保持不变。未列出的项目
+/// 按原有顺序排在列出的项目之后。
+fn apply_category_app_order(
+    storage: &mut AppStorage,
+    category_id: &str,
+    app_ids: &[i64],
+) -> Result<(), String> {
+    if category_id != ALL_CATEGORY_ID
+        && !storage
+            .categories
+            .iter()
+            .any(|category| category.id == category_id)
+    {
+        return Err(format!("分组不存在: {}", category_id));
+    }
+
+    let belongs_to_category = |app: &AppData| {
+        category_id == ALL_CATEGORY_ID || app.category_ids.iter().any(|id| id == category_id)
+    };
+
+    let mut seen_ids = HashSet::new();
+    for app_id in app_ids {
+        if !seen_ids.insert(*app_id) {
+            return Err(format!("项目 id 重复: {}", app_id));
+        }
+        let app = storage
+            .apps
+            .iter()
+            .find(|app| app.id == *app_id)
+            .ok_or_else(|| format!("项目不存在: {}", app_id))?;
+        if !belongs_to_category(app) {
+            return Err(format!("项目“{}”不属于该分组", app.name));
+        }
+    }
+
+    let mut remaining: Vec<(usize, Option<i32>)> = storage
+        .apps
+        .iter()
+        .enumerate()
+        .filter(|(_, app)| belongs_to_category(app) && !seen_ids.contains(&app.id))
+        .map(|(index, app)| (index, app_order_in_category(app, category_id)))
+        .collect();
+    remaining.sort_by_key(|(index, order)| (order.unwrap_or(i32::MAX), *index));
+
+    let ordered_indices = app_ids
+        .iter()
+        .filter_map(|app_id| storage.apps.iter().position(|app| app.id == *app_id))
+        .chain(remaining.into_iter().map(|(index, _)| index));
+    for (order, index) in ordered_indices.enumerate().collect::<Vec<_>>() {
+        let app = &mut storage.apps[index];
+        if category_id == ALL_CATEGORY_ID {
+            app.order = Some(order as i32);
+        } else {
+            app.category_orders
+                .insert(category_id.to_string(), order as i32);
+        }
+    }
+    Ok(())
+}
+
+/// 按给定的项目 id 顺序重排单个分组内的项目
+#[tauri::command]
+pub fn reorder_category_apps(
+    state: State<'_, AppState>,
+    category_id: String,
+    app_ids: Vec<i64>,
+) -> Result<String, String> {
+    state
+        .store
+        .update_library("调整分组内项目顺序", |storage| {
+            apply_category_app_order(storage, &category_id, &app_ids)
+        })?;
+    Ok("分组内项目顺序已更新".to_string())
+}
+
+/// 删除分组
+///
+/// 指定 `move_apps_to` 时，分组中的项目会改为归属目标分组；否则只移除项目对该
+/// 分组的归属，不再属于任何分组的项目会归入"全部应用"，项目本身不会被删除。
+/// 被删除的分组会移入回收站，恢复时会重新加入删除前属于它的项目。
+fn remove_category(
+    storage: &mut AppStorage,
+    category_id: &str,
+    move_apps_to: Option<&str>,
+    retention_days: u32,
+) -> Result<(), String> {
+    if category_id == ALL_CATEGORY_ID {
+        return Err("默认分组不能删除".to_string());
+    }
+    if move_apps_to == Some(category_id) {
+        return Err("不能把项目移动到正在删除的分组".to_string());
+    }
+
+    let category = storage
+        .categories
+        .iter()
+        .find(|category| category.id == category_id)
+        .ok_or_else(|| format!("分组不存在: {}", category_id))?;
+    if category.is_default {
+        return Err("默认分组不能删除".to_string());
+    }
+    if let Some(target_id) = move_apps_to.filter(|target_id| *target_id != ALL_CATEGORY_ID) {
+        if !storage
+            .categories
+            .iter()
+            .any(|category| category.id == target_id)
+        {
+            return Err(format!("目标分组不存在: {}", target_id));
+        }
+    }
+
+    let mut member_app_ids = Vec::new();
+    let mut app_orders = BTreeMap::new();
+    for app in &mut storage.apps {
+        normalize_app_categories(app);
+        let Some(position) = app.category_ids.iter().position(|id| *id == category_id) else {
+            continue;
+        };
+        member_app_ids.push(app.id);
+        // 保存项目在该分组中的排序，恢复分组时还原
+        if let Some(order) = app.category_orders.get(category_id) {
+            app_orders.insert(app.id, *order);
+        }
+
+        match move_apps_to {
+            Some(target_id) if !app.category_ids.iter().any(|id| id == target_id) => {
+                app.category_ids[position] = target_id.to_string();
+            }
+            _ => {
+                app.category_ids.remove(position);
+            }
+        }
+        app.category.clear();
+        normalize_app_categories(app);
+    }
+
+    let index = storage
+        .categories
+        .iter()
+        .position(|category| category.id == category_id)
+        .ok_or_else(|| format!("分组不存在: {}", category_id))?;
+    let removed_category = storage.categories.remove(index);
+    move_to_trash(
+        storage,
+        TrashItem::Category {
+            category: removed_category,
+            app_ids: member_app_ids,
+            app_orders,
+        },
+        retention_days,
+    );
+
+    if storage.selected_category.as_deref() == Some(category_id) {
+        storage.selected_category = Some(move_apps_to.unwrap_or(ALL_CATEGORY_ID).to_string());
+    }
+    Ok(())
+}
+
+/// 删除分组，分组移入回收站
+#[tauri::command]
+pub fn delete_category(
+    state: State<'_, AppState>,
+    category_id: String,
+    move_apps_to: Option<String>,
+) -> Result<String, String> {
+    let retention_days = trash_retention_days(&state.store);
+    state.store.update_library("删除分组", |storage| {
+        remove_category(
+            storage,
+            &category_id,
+            move_apps_to.as_deref(),
+            retention_days,
+        )
+    })?;
+
+    Ok("分组已移入回收站".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::STORAGE_SCHEMA_VERSION;
+
+    fn category(id: &str, order: i32) -> CategoryData {
+        CategoryData {
+            id: id.to_string(),
+            name: id.to_string(),
+            icon: DEFAULT_CATEGORY_ICON.to_string(),
+            is_default: id == ALL_CATEGORY_ID,
+            order: Some(order),
+            hidden: false,
+        }
+    }
+
+    fn app(id: i64, category_ids: &[&str]) -> AppData {
+        AppData {
+            id,
+            name: format!("app{}", id),
+            category: category_ids[0].to_string(),
+            category_ids: category_ids.iter().map(|id| id.to_string()).collect(),
+            icon: String::new(),
+            path: format!("/apps/{}", id),
+            target_path: None,
+            is_shortcut: false,
+            launch_args: None,
+            shortcut_hotkey: None,
+            target_type: None,
+            run_as_admin: false,
+            order: Some(id as i32),
+            category_orders: category_ids
+                .iter()
+                .map(|category_id| (category_id.to_string(), id as i32))
+                .collect(),
+            usage_count: None,
+            last_launched_at: None,
+        }
+    }
+
+    /// 分组 all、work、tools，项目 1 属于 work，2 属于 work 和 tools，3 属于 tools
+    fn sample_storage() -> AppStorage {
+        AppStorage {
+            schema_version: STORAGE_SCHEMA_VERSION,
+            apps: vec![
+                app(1, &["work"]),
+                app(2, &["work", "tools"]),
+                app(3, &["tools"]),
+            ],
+            categories: vec![
+                category("all", 0),
+                category("work", 1),
+                category("tools", 2),
+            ],
+            selected_category: Some("work".to_string()),
+            trash: Vec::new(),
+        }
+    }
+
+    fn find_app(storage: &AppStorage, id: i64) -> &AppData {
+        storage.apps.iter().find(|app| app.id == id).unwrap()
+    }
+
+    fn category_ids(storage: &AppStorage) -> Vec<&str> {
+        storage
+            .categories
+            .iter()
+            .map(|category| category.id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn create_category_appends_with_next_order() {
+        let mut storage = sample_storage();
+        let created =
+            add_category(&mut storage, " Games ", None, Some("games".to_string())).unwrap();
+        assert_eq!(created.name, "Games");
+        assert_eq!(created.icon, DEFAULT_CATEGORY_ICON);
+        assert_eq!(created.order, Some(3));
+        assert_eq!(storage.categories.last(), Some(&created));
+
+        let generated =
+            add_category(&mut storage, "Other", Some("icon-star".to_string()), None).unwrap();
+        assert_eq!(generated.icon, "icon-star");
+        assert_eq!(
+            storage
+                .categories
+                .iter()
+                .filter(|category| category.id == generated.id)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn create_category_rejects_duplicate_or_empty_ids_and_names() {
+        let mut storage = sample_storage();
+        assert!(add_category(&mut storage, "Work", None, Some("work".to_string())).is_err());
+        assert!(add_category(&mut storage, "Work", None, Some(" ".to_string())).is_err());
+        assert!(add_category(&mut storage, "  ", None, None).is_err());
+        assert_eq!(storage.categories.len(), 3);
+    }
+
+    #[test]
+    fn reorder_categories_keeps_all_first() {
+        let mut storage = sample_storage();
+        apply_category_order(&mut storage, &["tools".to_string(), "work".to_string()]).unwrap();
+        assert_eq!(category_ids(&storage), ["all", "tools", "work"]);
+        let orders: Vec<_> = storage
+            .categories
+            .iter()
+            .map(|category| category.order)
+            .collect();
+        assert_eq!(orders, [Some(0), Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn reorder_categories_rejects_duplicate_unknown_and_missing_ids() {
+        let mut storage = sample_storage();
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        assert!(apply_category_order(&mut storage, &ids(&["work", "work", "tools"])).is_err());
+        assert!(apply_category_order(&mut storage, &ids(&["work", "tools", "games"])).is_err());
+        assert!(apply_category_order(&mut storage, &ids(&["work"])).is_err());
+        assert_eq!(category_ids(&storage), ["all", "work", "tools"]);
+    }
+
+    #[test]
+    fn reorder_category_apps_only_changes_that_category() {
+        let mut storage = sample_storage();
+        apply_category_app_order(&mut storage, "work", &[2]).unwrap();
+        assert_eq!(find_app(&storage, 2).category_orders["work"], 0);
+        assert_eq!(find_app(&storage, 1).category_orders["work"], 1);
+        assert_eq!(find_app(&storage, 2).category_orders["tools"], 2);
+        assert_eq!(find_app(&storage, 2).order, Some(2));
+
+        apply_category_app_order(&mut storage, ALL_CATEGORY_ID, &[3, 1]).unwrap();
+        let orders: Vec<_> = [3, 1, 2]
+            .iter()
+            .map(|id| find_app(&storage, *id).order)
+            .collect();
+        assert_eq!(orders, [Some(0), Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn reorder_category_apps_rejects_invalid_ids() {
+        let mut storage = sample_storage();
+        assert!(apply_category_app_order(&mut storage, "games", &[1]).is_err());
+        assert!(apply_category_app_order(&mut storage, "work", &[1, 1]).is_err());
+        assert!(apply_category_app_order(&mut storage, "work", &[99]).is_err());
+        assert!(apply_category_app_order(&mut storage, "work", &[3]).is_err());
+    }
+
+    #[test]
+    fn delete_category_removes_only_deleted_membership() {
+        let mut storage = sample_storage();
+        remove_category(&mut storage, "work", None, 30).unwrap();
+
+        assert_eq!(category_ids(&storage), ["all", "tools"]);
+        // 只属于该分组的项目归入"全部应用"，其他项目只移除该分组的归属
+        let app1 = find_app(&storage, 1);
+        assert_eq!(app1.category_ids, ["all"]);
+        assert_eq!(app1.category, "all");
+        let app2 = find_app(&storage, 2);
+        assert_eq!(app2.category_ids, ["tools"]);
+        assert_eq!(app2.category, "tools");
+        assert!(!app2.category_orders.contains_key("work"));
+        assert_eq!(app2.category_orders["tools"], 2);
+        assert_eq!(find_app(&storage, 3).category_ids, ["tools"]);
+        assert_eq!(storage.selected_category.as_deref(), Some("all"));
+
+        match &storage.trash[..] {
+            [entry] => match &entry.item {
+                TrashItem::Category {
+                    category,
+                    app_ids,
+                    app_orders,
+                } => {
+                    assert_eq!(category.id, "work");
+                    assert_eq!(app_ids, &[1, 2]);
+                    assert_eq!(app_orders, &BTreeMap::from([(1, 1), (2, 2)]));
+                }
+                other => panic!("unexpected trash item: {:?}", other),
+            },
+            other => panic!("unexpected trash: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn delete_category_moves_apps_to_target() {
+        let mut storage = sample_storage();
+        remove_category(&mut storage, "work", Some("tools"), 30).unwrap();
+        assert_eq!(find_app(&storage, 1).category_ids, ["tools"]);
+        assert_eq!(find_app(&storage, 2).category_ids, ["tools"]);
+        assert_eq!(storage.selected_category.as_deref(), Some("tools"));
+    }
+
+    #[test]
+    fn delete_category_rejects_invalid_requests() {
+        let mut storage = sample_storage();
+        assert!(remove_category(&mut storage, ALL_CATEGORY_ID, None, 30).is_err());
+        assert!(remove_category(&mut storage, "games", None, 30).is_err());
+        assert!(remove_category(&mut storage, "work", Some("work"), 30).is_err());
+        assert!(remove_category(&mut storage, "work", Some("games"), 30).is_err());
+        assert_eq!(storage, sample_storage());
+    }
+}
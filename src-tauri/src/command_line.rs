@@ -0,0 +1,269 @@
+//! 启动参数拆分模块
+//!
+//! 把项目的 `launch_args` 字符串拆分为参数列表，规则与各平台一致：
+//! - Unix 按 POSIX shell 的引号和转义规则拆分：单引号内全部按原样保留，双引号内
+//!   反斜杠只转义 `$`、`` ` ``、`"`、`\` 和换行，引号外反斜杠转义下一个字符。
+//!   不经过 shell 执行，变量、通配符等不会展开
+//! - Windows 按 `CommandLineToArgvW` 的规则拆分：反斜杠只在引号前有特殊含义，
+//!   引号内连续两个引号表示一个引号字符
+//!
+//! Windows 上启动参数原样交给 ShellExecute，由目标程序自行拆分，这里的规则用于保存项目时
+//! 校验；其它平台用拆分结果直接启动程序。引号未闭合等无效输入返回错误。
+
+/// 按当前平台的规则拆分启动参数，未设置启动参数时返回空列表
+pub fn split_launch_args(launch_args: Option<&str>) -> Result<Vec<String>, String> {
+    match launch_args {
+        Some(launch_args) => split_args(launch_args),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// 按当前平台的规则拆分命令行参数
+pub fn split_args(args: &str) -> Result<Vec<String>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        split_windows_args(args)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        split_posix_args(args)
+    }
+}
+
+/// 按 POSIX shell 的引号和转义规则拆分参数
+#[cfg(any(not(target_os = "windows"), test))]
+fn split_posix_args(args: &str) -> Result<Vec<String>, String> {
+    let mut result = Vec::new();
+    let mut current = String::new();
+    // 引号可以产生空参数，需要单独记录当前参数是否已经开始
+    let mut in_arg = false;
+    let mut chars = args.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            ' ' | '\t' | '\n' => {
+                if in_arg {
+                    result.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            '\\' => match chars.next() {
+                // 反斜杠加换行是续行，两者都去掉
+                Some('\n') => {}
+                Some(escaped) => {
+                    current.push(escaped);
+                    in_arg = true;
+                }
+                None => return Err("末尾的反斜杠没有可转义的字符".to_string()),
+            },
+            '\'' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(quoted) => current.push(quoted),
+                        None => return Err("单引号未闭合".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('\n') => {}
+                            Some(escaped @ ('$' | '`' | '"' | '\\')) => current.push(escaped),
+                            Some(other) => {
+                                current.push('\\');
+                                current.push(other);
+                            }
+                            None => return Err("双引号未闭合".to_string()),
+                        },
+                        Some(quoted) => current.push(quoted),
+                        None => return Err("双引号未闭合".to_string()),
+                    }
+                }
+            }
+            _ => {
+                current.push(ch);
+                in_arg = true;
+            }
+        }
+    }
+
+    if in_arg {
+        result.push(current);
+    }
+    Ok(result)
+}
+
+/// 按 `CommandLineToArgvW` 的规则拆分参数
+///
+/// - 空格和制表符在引号外分隔参数
+/// - 2n 个反斜杠加引号得到 n 个反斜杠，引号切换引号状态；2n+1 个反斜杠加引号得到
+///   n 个反斜杠和一个引号字符；不在引号前的反斜杠按原样保留
+/// - 引号内连续两个引号得到一个引号字符并结束引号
+///
+/// 引号未闭合时 `CommandLineToArgvW` 会把剩余内容都当作引号内的内容，这里按无效输入处理。
+#[cfg(any(target_os = "windows", test))]
+fn split_windows_args(args: &str) -> Result<Vec<String>, String> {
+    let mut result = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut in_quotes = false;
+    let mut chars = args.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            ' ' | '\t' if !in_quotes => {
+                if in_arg {
+                    result.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            '\\' => {
+                let mut backslashes = 1;
+                while chars.next_if_eq(&'\\').is_some() {
+                    backslashes += 1;
+                }
+                in_arg = true;
+                if chars.peek() == Some(&'"') {
+                    current.push_str(&"\\".repeat(backslashes / 2));
+                    if backslashes % 2 == 1 {
+                        chars.next();
+                        current.push('"');
+                    }
+                } else {
+                    current.push_str(&"\\".repeat(backslashes));
+                }
+            }
+            '"' => {
+                in_arg = true;
+                if in_quotes && chars.next_if_eq(&'"').is_some() {
+                    current.push('"');
+                }
+                in_quotes = !in_quotes;
+            }
+            _ => {
+                current.push(ch);
+                in_arg = true;
+            }
+        }
+    }
+
+    if in_quotes {
+        return Err("双引号未闭合".to_string());
+    }
+    if in_arg {
+        result.push(current);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn posix_splits_on_whitespace() {
+        assert_eq!(
+            split_posix_args("  --name\tvalue\n -v ").unwrap(),
+            strings(&["--name", "value", "-v"])
+        );
+        assert_eq!(split_posix_args("").unwrap(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn posix_quotes() {
+        assert_eq!(
+            split_posix_args(r#"'a b' "c d" e'f'"g""#).unwrap(),
+            strings(&["a b", "c d", "efg"])
+        );
+        assert_eq!(
+            split_posix_args(r#"'' "" x"#).unwrap(),
+            strings(&["", "", "x"])
+        );
+        // 单引号内反斜杠和 $ 按原样保留
+        assert_eq!(
+            split_posix_args(r"'C:\dir\$HOME'").unwrap(),
+            strings(&[r"C:\dir\$HOME"])
+        );
+    }
+
+    #[test]
+    fn posix_escapes() {
+        assert_eq!(
+            split_posix_args(r"a\ b \'c\' \\").unwrap(),
+            strings(&["a b", "'c'", "\\"])
+        );
+        // 双引号内反斜杠只转义 $、`、"、\，其它情况按原样保留
+        assert_eq!(
+            split_posix_args(r#""\$x \` \" \\ \n""#).unwrap(),
+            strings(&[r#"$x ` " \ \n"#])
+        );
+        assert_eq!(
+            split_posix_args("a\\\nb \"c\\\nd\"").unwrap(),
+            strings(&["ab", "cd"])
+        );
+    }
+
+    #[test]
+    fn posix_rejects_unclosed_quotes() {
+        assert!(split_posix_args("'abc").is_err());
+        assert!(split_posix_args("\"abc").is_err());
+        assert!(split_posix_args(r#""abc\""#).is_err());
+        assert!(split_posix_args("abc\\").is_err());
+    }
+
+    #[test]
+    fn windows_splits_on_spaces_and_tabs() {
+        assert_eq!(
+            split_windows_args(" a\tb  \"c d\" ").unwrap(),
+            strings(&["a", "b", "c d"])
+        );
+        assert_eq!(split_windows_args(r#""" x"#).unwrap(), strings(&["", "x"]));
+    }
+
+    #[test]
+    fn windows_backslashes_are_literal_unless_before_quote() {
+        assert_eq!(
+            split_windows_args(r"C:\dir\file.txt \\server\share").unwrap(),
+            strings(&[r"C:\dir\file.txt", r"\\server\share"])
+        );
+        // 2n+1 个反斜杠加引号得到 n 个反斜杠和一个引号字符
+        assert_eq!(
+            split_windows_args(r#"a\"b c\\\"d"#).unwrap(),
+            strings(&[r#"a"b"#, r#"c\"d"#])
+        );
+        // 2n 个反斜杠加引号得到 n 个反斜杠，引号切换引号状态
+        assert_eq!(
+            split_windows_args(r#""C:\dir\\" next"#).unwrap(),
+            strings(&[r"C:\dir\", "next"])
+        );
+    }
+
+    #[test]
+    fn windows_doubled_quote_inside_quotes() {
+        assert_eq!(
+            split_windows_args(r#""a""b c"#).unwrap(),
+            strings(&[r#"a"b"#, "c"])
+        );
+    }
+
+    #[test]
+    fn windows_rejects_unclosed_quotes() {
+        assert!(split_windows_args(r#""abc"#).is_err());
+        assert!(split_windows_args(r#"a \\"b"#).is_err());
+    }
+
+    #[test]
+    fn split_launch_args_without_args() {
+        assert_eq!(split_launch_args(None).unwrap(), Vec::<String>::new());
+    }
+}
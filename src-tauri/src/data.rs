@@ -1,7 +1,24 @@
+use crate::backup::{keep_backup_bookkeeping, BackupManager};
+use crate::backup_archive::{is_archive, is_archive_path, read_archive, write_archive};
+use crate::backup_schedule::BackupSchedule;
+use crate::category_manager::validate_category_ids;
+use crate::data_scope::{select_categories, DataScope};
+use crate::import_merge::{merge_storage, ConflictPolicy};
+use crate::migration::{migrate_settings, migrate_storage};
 use crate::models::*;
+use crate::settings_manager::{
+    apply_auto_start, validate_backup_debounce_seconds, validate_backup_format,
+    validate_backup_min_interval_minutes, validate_backup_retention, validate_trash_retention_days,
+};
+use crate::store::Store;
+use crate::trash::{move_to_trash, trash_retention_days};
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 use std::fs;
-use tauri::AppHandle;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::{AppHandle, State};
 use tauri_plugin_global_shortcut::Shortcut;
 
 // 获取应用数据目录
@@ -16,7 +33,207 @@ pub fn get_app_data_dir() -> Result<std::path::PathBuf, String> {
     Ok(data_dir)
 }
 
-fn normalize_app_categories(app: &mut AppData) {
+fn sibling_path(file_path: &Path, suffix: &str) -> PathBuf {
+    let mut file_name = file_path
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_default();
+    file_name.push(suffix);
+    file_path.with_file_name(file_name)
+}
+
+/// 临时文件序号，与进程 ID 一起保证并发写入时临时文件名不重复
+static TEMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// 原子写入文件
+///
+/// 先写入同目录下的临时文件并落盘，再重命名覆盖目标文件，最后落盘所在目录使重命名持久化，
+/// 保证崩溃或断电时目标文件要么是旧内容，要么是完整的新内容。
+pub fn write_file_atomic(file_path: &Path, contents: &[u8]) -> Result<(), String> {
+    let temp_suffix = format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    );
+    let temp_path = sibling_path(file_path, &temp_suffix);
+
+    let write_result = (|| {
+        let mut file = fs::File::create(&temp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()
+    })();
+    if let Err(e) = write_result {
+        let _ = fs::remove_file(&temp_path);
+        return Err(format!("写入临时文件失败: {}", e));
+    }
+
+    if let Err(e) = fs::rename(&temp_path, file_path) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(format!("替换文件失败: {}", e));
+    }
+
+    // Windows 不支持打开目录落盘，重命名由文件系统日志保证
+    #[cfg(unix)]
+    {
+        let dir_path = match file_path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        fs::File::open(dir_path)
+            .and_then(|dir| dir.sync_all())
+            .map_err(|e| format!("同步目录失败: {}", e))?;
+    }
+
+    Ok(())
+}
+
+// 写入数据文件，覆盖前把仍可解析的旧文件保留为 .bak 作为最后一份完好副本
+fn save_data_file(file_path: &Path, contents: &[u8]) -> Result<(), String> {
+    if let Ok(existing) = fs::read(file_path) {
+        if serde_json::from_slice::<Value>(&existing).is_ok() {
+            let _ = fs::copy(file_path, sibling_path(file_path, ".bak"));
+        }
+    }
+
+    write_file_atomic(file_path, contents)
+}
+
+/// 数据迁移函数，把读取到的 JSON 升级到当前版本，返回是否发生了迁移
+type MigrateFn = fn(&mut Value) -> Result<bool, String>;
+
+// 迁移并反序列化数据，返回数据以及是否发生了迁移
+fn decode_data_value<T: DeserializeOwned>(
+    mut value: Value,
+    migrate: MigrateFn,
+) -> Result<(T, bool), String> {
+    let migrated = migrate(&mut value)?;
+    let data = serde_json::from_value(value).map_err(|e| e.to_string())?;
+    Ok((data, migrated))
+}
+
+fn decode_data_bytes<T: DeserializeOwned>(json_data: &[u8], migrate: MigrateFn) -> Option<T> {
+    let value = serde_json::from_slice::<Value>(json_data).ok()?;
+    decode_data_value(value, migrate).ok().map(|(data, _)| data)
+}
+
+// 从自动备份目录中按时间倒序查找第一份可解析的对应数据
+fn recover_from_backups<T: DeserializeOwned>(
+    backup_key: &str,
+    migrate: MigrateFn,
+) -> Option<(T, PathBuf)> {
+    let mut backup_files: Vec<_> = crate::backup::recovery_backup_dirs()
+        .iter()
+        .flat_map(|backup_dir| crate::backup::list_backup_files(backup_dir))
+        .collect();
+    backup_files.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+
+    backup_files.into_iter().find_map(|(path, _)| {
+        let json_data = fs::read(&path).ok()?;
+        let value = if is_archive(&json_data) {
+            let archive = read_archive(&json_data).ok()?;
+            match backup_key {
+                "storage" => serde_json::to_value(archive.storage?).ok()?,
+                "settings" => serde_json::to_value(archive.settings?).ok()?,
+                _ => return None,
+            }
+        } else {
+            let backup_data: Value = serde_json::from_slice(&json_data).ok()?;
+            backup_data.get(backup_key)?.clone()
+        };
+        decode_data_value(value, migrate)
+            .ok()
+            .map(|(data, _)| (data, path))
+    })
+}
+
+/// 读取数据文件，迁移到当前版本，解析失败时自动恢复
+///
+/// 旧版本的数据会在迁移后立即写回。解析失败时依次尝试 .bak 副本和
+/// backups 目录中最新的可用备份，恢复成功后会把损坏的文件另存为
+/// .corrupt-时间戳 并写回恢复出的数据。文件不存在时返回 None。
+fn read_data_file<T: DeserializeOwned + serde::Serialize>(
+    file_path: &Path,
+    backup_key: &str,
+    migrate: MigrateFn,
+) -> Result<Option<T>, String> {
+    if !file_path.exists() {
+        return Ok(None);
+    }
+
+    let json_data = fs::read(file_path).map_err(|e| format!("读取文件失败: {}", e))?;
+    let parse_error = match serde_json::from_slice::<Value>(&json_data) {
+        Ok(mut value) => {
+            // 版本过高等迁移错误不是文件损坏，不应使用备份覆盖
+            let migrated = migrate(&mut value)?;
+            match serde_json::from_value::<T>(value) {
+                Ok(data) => {
+                    if migrated {
+                        let json_data = serde_json::to_string_pretty(&data)
+                            .map_err(|e| format!("序列化数据失败: {}", e))?;
+                        save_data_file(file_path, json_data.as_bytes())?;
+                    }
+                    return Ok(Some(data));
+                }
+                Err(e) => e,
+            }
+        }
+        Err(e) => e,
+    };
+
+    eprintln!(
+        "解析 {} 失败: {}，尝试从备份恢复",
+        file_path.display(),
+        parse_error
+    );
+
+    let bak_path = sibling_path(file_path, ".bak");
+    let recovered = fs::read(&bak_path)
+        .ok()
+        .and_then(|data| decode_data_bytes::<T>(&data, migrate))
+        .map(|data| (data, bak_path))
+        .or_else(|| recover_from_backups::<T>(backup_key, migrate));
+
+    let Some((data, source)) = recovered else {
+        return Err(format!("解析数据失败: {}", parse_error));
+    };
+
+    let corrupt_path = sibling_path(
+        file_path,
+        &format!(".corrupt-{}", chrono::Local::now().format("%Y%m%d_%H%M%S")),
+    );
+    let _ = fs::rename(file_path, &corrupt_path);
+
+    let json_data =
+        serde_json::to_string_pretty(&data).map_err(|e| format!("序列化数据失败: {}", e))?;
+    write_file_atomic(file_path, json_data.as_bytes())?;
+
+    eprintln!(
+        "已从 {} 恢复 {}，损坏的文件已保存为 {}",
+        source.display(),
+        file_path.display(),
+        corrupt_path.display()
+    );
+
+    Ok(Some(data))
+}
+
+/// 解析导入或备份文件中的应用数据，并迁移到当前版本
+pub fn parse_storage_value(value: Value) -> Result<AppStorage, String> {
+    decode_data_value(value, migrate_storage)
+        .map(|(storage, _)| storage)
+        .map_err(|e| format!("解析存储数据失败: {}", e))
+}
+
+/// 解析导入或备份文件中的设置，并迁移到当前版本
+pub fn parse_settings_value(value: Value) -> Result<AppSettings, String> {
+    decode_data_value(value, migrate_settings)
+        .map(|(settings, _)| settings)
+        .map_err(|e| format!("解析设置数据失败: {}", e))
+}
+
+/// 整理项目的分组归属：去除空值和重复项，`category` 始终为第一个分组，
+/// 并清理已不属于的分组中的排序
+pub fn normalize_app_categories(app: &mut AppData) {
     let mut category_ids: Vec<String> = Vec::new();
 
     if !app.category.trim().is_empty() {
@@ -35,9 +252,19 @@ fn normalize_app_categories(app: &mut AppData) {
 
     app.category = category_ids[0].clone();
     app.category_ids = category_ids;
+
+    // "全部应用"使用 order 排序，其他分组只保留项目仍然所属分组的排序
+    let category_ids = &app.category_ids;
+    app.category_orders
+        .retain(|category_id, _| category_id != "all" && category_ids.contains(category_id));
 }
 
-fn validate_project_hotkey(storage: &AppStorage, app: &mut AppData) -> Result<(), String> {
+/// 校验项目快捷键格式，并检查是否与显示/隐藏快捷键或其他项目冲突
+pub fn validate_project_hotkey(
+    storage: &AppStorage,
+    settings: Option<&AppSettings>,
+    app: &mut AppData,
+) -> Result<(), String> {
     let Some(hotkey) = app
         .shortcut_hotkey
         .as_deref()
@@ -55,7 +282,7 @@ fn validate_project_hotkey(storage: &AppStorage, app: &mut AppData) -> Result<()
         return Err("项目快捷键必须包含 Ctrl、Alt、Shift 或 Win 修饰键".to_string());
     }
 
-    if let Ok(settings) = load_app_settings() {
+    if let Some(settings) = settings {
         let conflicts_with_window_toggle = settings
             .toggle_hotkey
             .as_deref()
@@ -83,138 +310,161 @@ fn validate_project_hotkey(storage: &AppStorage, app: &mut AppData) -> Result<()
     Ok(())
 }
 
-fn rollback_shortcut_registration(app: &AppHandle, storage: AppStorage, error: String) -> String {
-    if let Err(rollback_error) =
-        save_app_data(storage.apps, storage.categories, storage.selected_category)
-    {
-        return format!("{}；回滚数据失败: {}", error, rollback_error);
+/// 校验启动参数能否按当前平台的规则拆分，避免引号未闭合的参数在启动时被错误拆分
+pub fn validate_launch_args(app: &AppData) -> Result<(), String> {
+    crate::command_line::split_launch_args(app.launch_args.as_deref())
+        .map(|_| ())
+        .map_err(|e| format!("启动参数格式无效: {}", e))
+}
+
+/// 校验一组项目的启动参数，出错时指出是哪个项目
+pub fn validate_apps_launch_args(apps: &[AppData]) -> Result<(), String> {
+    apps.iter().try_for_each(|app| {
+        validate_launch_args(app).map_err(|e| format!("项目“{}”的{}", app.name, e))
+    })
+}
+
+/// 快捷键注册失败时回滚刚刚提交的修改，并重新注册原有快捷键
+///
+/// `entry_id` 为提交修改时记录的历史 id，数据没有变化时为 None，不需要回滚。
+pub fn rollback_shortcut_registration(
+    app: &AppHandle,
+    store: &Store,
+    entry_id: Option<u64>,
+    error: String,
+) -> String {
+    if let Some(entry_id) = entry_id {
+        if let Err(rollback_error) = store.rollback_library(entry_id) {
+            return format!("{}；回滚数据失败: {}", error, rollback_error);
+        }
     }
     let _ = crate::system_integration::refresh_global_shortcuts(app);
     error
 }
 
-#[tauri::command]
-pub fn save_app_data(
-    mut apps: Vec<AppData>,
-    categories: Vec<CategoryData>,
-    selected_category: Option<String>,
-) -> Result<String, String> {
+fn sort_categories(categories: &mut [CategoryData]) {
+    categories.sort_by(|a, b| {
+        let a_order = if a.id == "all" {
+            i32::MIN
+        } else {
+            a.order.unwrap_or(i32::MAX)
+        };
+        let b_order = if b.id == "all" {
+            i32::MIN
+        } else {
+            b.order.unwrap_or(i32::MAX)
+        };
+        a_order
+            .cmp(&b_order)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+}
+
+/// 从磁盘读取应用数据，仅在启动时由 Store 调用
+pub fn read_app_data_file() -> Result<AppStorage, String> {
     let data_dir = get_app_data_dir()?;
     let file_path = data_dir.join("apps.json");
 
-    for app in &mut apps {
-        normalize_app_categories(app);
-    }
+    let storage = read_data_file::<AppStorage>(&file_path, "storage", migrate_storage)?;
 
-    let storage = AppStorage {
-        apps,
-        categories,
-        selected_category,
-    };
-    let json_data =
-        serde_json::to_string_pretty(&storage).map_err(|e| format!("序列化数据失败: {}", e))?;
-
-    fs::write(&file_path, json_data).map_err(|e| format!("保存文件失败: {}", e))?;
-
-    Ok("数据保存成功".to_string())
+    Ok(storage.unwrap_or_else(|| AppStorage {
+        schema_version: STORAGE_SCHEMA_VERSION,
+        apps: vec![],
+        categories: vec![],
+        selected_category: Some("all".to_string()),
+        trash: vec![],
+    }))
 }
 
-#[tauri::command]
-pub fn load_app_data() -> Result<AppStorage, String> {
+/// 把应用数据写入磁盘，应通过 Store 调用以保证串行写入
+pub fn write_app_data_file(storage: &AppStorage) -> Result<(), String> {
     let data_dir = get_app_data_dir()?;
     let file_path = data_dir.join("apps.json");
 
-    if !file_path.exists() {
-        return Ok(AppStorage {
-            apps: vec![],
-            categories: vec![],
-            selected_category: Some("all".to_string()),
-        });
-    }
-
-    let json_data = match std::fs::read(&file_path) {
-        Ok(data) => data,
-        Err(e) => return Err(format!("读取文件失败: {}", e)),
-    };
-
-    let mut storage: AppStorage = match serde_json::from_slice(&json_data) {
-        Ok(storage) => storage,
-        Err(e) => return Err(format!("解析数据失败: {}", e)),
-    };
-
-    // 为没有 order 字段的历史数据自动分配排序值
-    // 按分类分组，每个分类内按 id 顺序分配 order
-    let mut category_order_map: std::collections::HashMap<String, i32> =
-        std::collections::HashMap::new();
-
-    for app in &mut storage.apps {
-        normalize_app_categories(app);
+    let json_data =
+        serde_json::to_string_pretty(storage).map_err(|e| format!("序列化数据失败: {}", e))?;
 
-        if app.order.is_none() {
-            let category = &app.category;
-            let next_order = category_order_map.entry(category.clone()).or_insert(0);
-            app.order = Some(*next_order);
-            *next_order += 1;
-        }
-        if app.usage_count.is_none() {
-            app.usage_count = Some(0);
-        }
-    }
+    save_data_file(&file_path, json_data.as_bytes()).map_err(|e| format!("保存文件失败: {}", e))
+}
 
-    for (index, category) in storage.categories.iter_mut().enumerate() {
-        if category.order.is_none() {
-            category.order = Some(if category.id == "all" { 0 } else { index as i32 });
-        }
-    }
+/// 从磁盘读取设置，仅在启动时由 Store 调用
+pub fn read_app_settings_file() -> Result<AppSettings, String> {
+    let data_dir = get_app_data_dir()?;
+    let file_path = data_dir.join("settings.json");
 
-    storage.categories.sort_by(|a, b| {
-        let a_order = if a.id == "all" { i32::MIN } else { a.order.unwrap_or(i32::MAX) };
-        let b_order = if b.id == "all" { i32::MIN } else { b.order.unwrap_or(i32::MAX) };
-        a_order
-            .cmp(&b_order)
-            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
-    });
+    let settings = read_data_file::<AppSettings>(&file_path, "settings", migrate_settings)
+        .map_err(|e| format!("加载设置失败: {}", e))?;
 
-    Ok(storage)
+    Ok(settings.unwrap_or_else(get_default_settings))
 }
 
-#[tauri::command]
-pub fn save_app_settings(settings: AppSettings) -> Result<String, String> {
+/// 把设置写入磁盘，应通过 Store 调用以保证串行写入
+pub fn write_app_settings_file(settings: &AppSettings) -> Result<(), String> {
     let data_dir = get_app_data_dir()?;
     let file_path = data_dir.join("settings.json");
 
     let json_data =
-        serde_json::to_string_pretty(&settings).map_err(|e| format!("序列化设置失败: {}", e))?;
+        serde_json::to_string_pretty(settings).map_err(|e| format!("序列化设置失败: {}", e))?;
 
-    fs::write(&file_path, json_data).map_err(|e| format!("保存设置文件失败: {}", e))?;
-
-    Ok("设置保存成功".to_string())
+    save_data_file(&file_path, json_data.as_bytes()).map_err(|e| format!("保存设置文件失败: {}", e))
 }
 
 #[tauri::command]
-pub fn load_app_settings() -> Result<AppSettings, String> {
-    let data_dir = get_app_data_dir()?;
-    let file_path = data_dir.join("settings.json");
-
-    if !file_path.exists() {
-        return Ok(get_default_settings());
+pub fn save_app_data(
+    state: State<'_, AppState>,
+    mut apps: Vec<AppData>,
+    categories: Vec<CategoryData>,
+    selected_category: Option<String>,
+) -> Result<String, String> {
+    validate_category_ids(&categories)?;
+    validate_apps_launch_args(&apps)?;
+    for app in &mut apps {
+        normalize_app_categories(app);
     }
 
-    let json_data = match std::fs::read(&file_path) {
-        Ok(data) => data,
-        Err(e) => return Err(format!("读取设置文件失败: {}", e)),
-    };
+    state.store.update_library("保存数据", |storage| {
+        storage.apps = apps;
+        storage.categories = categories;
+        storage.selected_category = selected_category;
+        Ok(())
+    })?;
 
-    let settings: AppSettings = match serde_json::from_slice(&json_data) {
-        Ok(settings) => settings,
-        Err(e) => return Err(format!("解析设置失败: {}", e)),
-    };
+    Ok("数据保存成功".to_string())
+}
+
+#[tauri::command]
+pub fn load_app_data(state: State<'_, AppState>) -> Result<AppStorage, String> {
+    let mut storage = state.store.storage()?;
+    sort_categories(&mut storage.categories);
+    Ok(storage)
+}
+
+#[tauri::command]
+pub fn save_app_settings(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    settings: AppSettings,
+) -> Result<String, String> {
+    let previous_settings = state.store.settings().ok();
+    apply_auto_start(&settings)?;
+    if let Err(error) = state.store.replace_settings(settings) {
+        if let Some(previous_settings) = &previous_settings {
+            let _ = apply_auto_start(previous_settings);
+        }
+        return Err(error);
+    }
+    BackupManager::restart_scheduler(&app);
+    Ok("设置保存成功".to_string())
+}
 
-    Ok(settings)
+#[tauri::command]
+pub fn load_app_settings(state: State<'_, AppState>) -> Result<AppSettings, String> {
+    state.store.settings()
 }
 
 pub fn get_default_settings() -> AppSettings {
     AppSettings {
+        schema_version: SETTINGS_SCHEMA_VERSION,
         prevent_auto_hide: false,
         window_width: Some(800),
         window_height: Some(600),
@@ -243,6 +493,14 @@ pub fn get_default_settings() -> AppSettings {
         backup_interval: Some("weekly".to_string()),
         last_backup_time: None,
         next_backup_time: None,
+        backup_retention: Some(BackupRetention::default()),
+        trash_retention_days: Some(30),
+        backup_format: Some("zip".to_string()),
+        backup_dir: None,
+        backup_on_change: Some(false),
+        backup_debounce_seconds: Some(300),
+        backup_min_interval_minutes: Some(30),
+        last_backup_hash: None,
         active_tab: Some("about".to_string()),
         last_selected_category: None,
         window_position_x: None,
@@ -255,25 +513,36 @@ pub fn get_default_settings() -> AppSettings {
 }
 
 #[tauri::command]
-pub fn save_window_size(width: u32, height: u32) -> Result<String, String> {
-    let mut settings = load_app_settings()?;
-    settings.window_width = Some(width);
-    settings.window_height = Some(height);
-    save_app_settings(settings)?;
+pub fn save_window_size(
+    state: State<'_, AppState>,
+    width: u32,
+    height: u32,
+) -> Result<String, String> {
+    state.store.update_settings(|settings| {
+        settings.window_width = Some(width);
+        settings.window_height = Some(height);
+        Ok(())
+    })?;
     Ok("窗口大小保存成功".to_string())
 }
 
 #[tauri::command]
-pub fn save_settings_window_size(width: u32, height: u32) -> Result<String, String> {
-    let mut settings = load_app_settings()?;
-    settings.settings_window_width = Some(width);
-    settings.settings_window_height = Some(height);
-    save_app_settings(settings)?;
+pub fn save_settings_window_size(
+    state: State<'_, AppState>,
+    width: u32,
+    height: u32,
+) -> Result<String, String> {
+    state.store.update_settings(|settings| {
+        settings.settings_window_width = Some(width);
+        settings.settings_window_height = Some(height);
+        Ok(())
+    })?;
     Ok("设置窗口大小保存成功".to_string())
 }
 
 #[tauri::command]
 pub fn save_ui_state(
+    state: State<'_, AppState>,
     active_tab: Option<String>,
     last_selected_category: Option<String>,
     window_position_x: Option<i32>,
@@ -283,41 +552,38 @@ pub fn save_ui_state(
     sort_order: Option<String>,
     show_hidden_files: Option<bool>,
 ) -> Result<String, String> {
-    let mut settings = load_app_settings()?;
-
-    if let Some(tab) = active_tab {
-        settings.active_tab = Some(tab);
-    }
-    if let Some(category) = last_selected_category {
-        settings.last_selected_category = Some(category);
-    }
-    if let Some(x) = window_position_x {
-        settings.window_position_x = Some(x);
-    }
-    if let Some(y) = window_position_y {
-        settings.window_position_y = Some(y);
-    }
-    if let Some(query) = last_search_query {
-        settings.last_search_query = Some(query);
-    }
-    if let Some(grid_view) = grid_view_enabled {
-        settings.grid_view_enabled = Some(grid_view);
-    }
-    if let Some(sort) = sort_order {
-        settings.sort_order = Some(sort);
-    }
-    if let Some(hidden) = show_hidden_files {
-        settings.show_hidden_files = Some(hidden);
-    }
+    state.store.update_settings(|settings| {
+        if let Some(tab) = active_tab {
+            settings.active_tab = Some(tab);
+        }
+        if let Some(category) = last_selected_category {
+            settings.last_selected_category = Some(category);
+        }
+        if let Some(x) = window_position_x {
+            settings.window_position_x = Some(x);
+        }
+        if let Some(y) = window_position_y {
+            settings.window_position_y = Some(y);
+        }
+        if let Some(query) = last_search_query {
+            settings.last_search_query = Some(query);
+        }
+        if let Some(grid_view) = grid_view_enabled {
+            settings.grid_view_enabled = Some(grid_view);
+        }
+        if let Some(sort) = sort_order {
+            settings.sort_order = Some(sort);
+        }
+        if let Some(hidden) = show_hidden_files {
+            settings.show_hidden_files = Some(hidden);
+        }
+        Ok(())
+    })?;
 
-    save_app_settings(settings)?;
     Ok("界面状态已保存".to_string())
 }
 
-#[tauri::command]
-pub fn update_settings_batch(settings_update: Value) -> Result<String, String> {
-    let mut settings = load_app_settings()?;
-
+fn apply_settings_batch(settings: &mut AppSettings, settings_update: &Value) -> Result<(), String> {
     if let Some(prevent_auto_hide) = settings_update
         .get("preventAutoHide")
         .and_then(|v| v.as_bool())
@@ -409,96 +675,220 @@ pub fn update_settings_batch(settings_update: Value) -> Result<String, String> {
         .get("backupInterval")
         .and_then(|v| v.as_str())
     {
-        settings.backup_interval = Some(backup_interval.to_string());
+        BackupSchedule::parse(backup_interval)?;
+        settings.backup_interval = Some(backup_interval.trim().to_string());
+    }
+    if let Some(backup_retention) = settings_update
+        .get("backupRetention")
+        .and_then(|v| serde_json::from_value::<BackupRetention>(v.clone()).ok())
+    {
+        validate_backup_retention(&backup_retention)?;
+        settings.backup_retention = Some(backup_retention);
+    }
+    if let Some(trash_retention_days) = settings_update
+        .get("trashRetentionDays")
+        .and_then(|v| v.as_u64())
+    {
+        let trash_retention_days = u32::try_from(trash_retention_days).unwrap_or(u32::MAX);
+        validate_trash_retention_days(trash_retention_days)?;
+        settings.trash_retention_days = Some(trash_retention_days);
+    }
+    if let Some(backup_format) = settings_update.get("backupFormat").and_then(|v| v.as_str()) {
+        validate_backup_format(backup_format)?;
+        settings.backup_format = Some(backup_format.to_string());
+    }
+    if let Some(backup_on_change) = settings_update
+        .get("backupOnChange")
+        .and_then(|v| v.as_bool())
+    {
+        settings.backup_on_change = Some(backup_on_change);
+    }
+    if let Some(backup_debounce_seconds) = settings_update
+        .get("backupDebounceSeconds")
+        .and_then(|v| v.as_u64())
+    {
+        let backup_debounce_seconds = u32::try_from(backup_debounce_seconds).unwrap_or(u32::MAX);
+        validate_backup_debounce_seconds(backup_debounce_seconds)?;
+        settings.backup_debounce_seconds = Some(backup_debounce_seconds);
+    }
+    if let Some(backup_min_interval_minutes) = settings_update
+        .get("backupMinIntervalMinutes")
+        .and_then(|v| v.as_u64())
+    {
+        let backup_min_interval_minutes =
+            u32::try_from(backup_min_interval_minutes).unwrap_or(u32::MAX);
+        validate_backup_min_interval_minutes(backup_min_interval_minutes)?;
+        settings.backup_min_interval_minutes = Some(backup_min_interval_minutes);
     }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn update_settings_batch(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    settings_update: Value,
+) -> Result<String, String> {
+    let previous_settings = state.store.settings()?;
+    let mut auto_start_applied = false;
+    let result = state.store.update_settings(|settings| {
+        let previous_schedule = (settings.auto_backup, settings.backup_interval.clone());
+        let previous_auto_start = (settings.start_with_system, settings.start_minimized);
+        apply_settings_batch(settings, &settings_update)?;
+
+        // 开机自启动相关设置变化时先更新系统的自启动项，失败时不保存
+        if (settings.start_with_system, settings.start_minimized) != previous_auto_start {
+            apply_auto_start(settings)?;
+            auto_start_applied = true;
+        }
 
-    save_app_settings(settings)?;
+        // 备份间隔变化后按新的间隔从现在开始重新计算下次备份时间
+        if settings.backup_interval != previous_schedule.1 {
+            if let Some(next_time) = settings
+                .backup_interval
+                .as_deref()
+                .and_then(|interval| BackupSchedule::parse(interval).ok())
+                .and_then(|schedule| schedule.next_after(chrono::Local::now()).ok())
+            {
+                settings.next_backup_time = Some(next_time.timestamp());
+            }
+        }
+        Ok((settings.auto_backup, settings.backup_interval.clone()) != previous_schedule)
+    });
+    let schedule_changed = match result {
+        Ok(schedule_changed) => schedule_changed,
+        Err(error) => {
+            // 保存失败时把系统的自启动项恢复为原来的设置
+            if auto_start_applied {
+                let _ = apply_auto_start(&previous_settings);
+            }
+            return Err(error);
+        }
+    };
+    if schedule_changed {
+        BackupManager::restart_scheduler(&app);
+    }
     Ok("设置已批量更新".to_string())
 }
 
 #[tauri::command]
-pub fn delete_app(app: AppHandle, app_id: i64) -> Result<String, String> {
-    let mut storage = load_app_data()?;
-    let previous_storage = storage.clone();
-    storage.apps.retain(|app| app.id != app_id);
-    save_app_data(storage.apps, storage.categories, storage.selected_category)?;
+pub fn delete_app(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    app_id: i64,
+) -> Result<String, String> {
+    let retention_days = trash_retention_days(&state.store);
+    let ((), entry_id) = state
+        .store
+        .update_library_revertible("删除项目", |storage| {
+            let index = storage
+                .apps
+                .iter()
+                .position(|app| app.id == app_id)
+                .ok_or("应用不存在")?;
+            let removed_app = storage.apps.remove(index);
+            move_to_trash(storage, TrashItem::App { app: removed_app }, retention_days);
+            Ok(())
+        })?;
     if let Err(error) = crate::system_integration::refresh_global_shortcuts(&app) {
         return Err(rollback_shortcut_registration(
             &app,
-            previous_storage,
+            &state.store,
+            entry_id,
             error,
         ));
     }
-    Ok("应用删除成功".to_string())
+    Ok("应用已移入回收站".to_string())
 }
 
 #[tauri::command]
-pub fn update_app_category(app_id: i64, new_category: String) -> Result<String, String> {
-    let mut storage = load_app_data()?;
-
-    if let Some(app) = storage.apps.iter_mut().find(|app| app.id == app_id) {
+pub fn update_app_category(
+    state: State<'_, AppState>,
+    app_id: i64,
+    new_category: String,
+) -> Result<String, String> {
+    state.store.update_library("移动项目分组", |storage| {
+        let app = storage
+            .apps
+            .iter_mut()
+            .find(|app| app.id == app_id)
+            .ok_or("应用不存在")?;
         app.category = new_category.clone();
         app.category_ids = vec![new_category];
-        save_app_data(storage.apps, storage.categories, storage.selected_category)?;
-        Ok("应用分类更新成功".to_string())
-    } else {
-        Err("应用不存在".to_string())
-    }
+        normalize_app_categories(app);
+        Ok(())
+    })?;
+    Ok("应用分类更新成功".to_string())
 }
 
 #[tauri::command]
-pub fn save_selected_category(category_id: String) -> Result<String, String> {
-    let mut storage = load_app_data()?;
-    storage.selected_category = Some(category_id);
-    save_app_data(storage.apps, storage.categories, storage.selected_category)?;
+pub fn save_selected_category(
+    state: State<'_, AppState>,
+    category_id: String,
+) -> Result<String, String> {
+    state.store.update_storage(|storage| {
+        storage.selected_category = Some(category_id);
+        Ok(())
+    })?;
     Ok("选中分组保存成功".to_string())
 }
 
 #[tauri::command]
-pub fn update_category_hidden(category_id: String, hidden: bool) -> Result<String, String> {
+pub fn update_category_hidden(
+    state: State<'_, AppState>,
+    category_id: String,
+    hidden: bool,
+) -> Result<String, String> {
     if category_id == "all" {
         return Err("默认分组不能隐藏".to_string());
     }
 
-    let mut storage = load_app_data()?;
+    state.store.update_library("修改分组显示状态", |storage| {
+        let category = storage
+            .categories
+            .iter_mut()
+            .find(|category| category.id == category_id)
+            .ok_or("分组不存在")?;
 
-    if let Some(category) = storage
-        .categories
-        .iter_mut()
-        .find(|category| category.id == category_id)
-    {
         if category.is_default {
             return Err("默认分组不能隐藏".to_string());
         }
 
         category.hidden = hidden;
-        if hidden && storage.selected_category.as_deref() == Some(category.id.as_str()) {
+        if hidden && storage.selected_category.as_deref() == Some(category_id.as_str()) {
             storage.selected_category = Some("all".to_string());
         }
+        Ok(())
+    })?;
 
-        save_app_data(storage.apps, storage.categories, storage.selected_category)?;
-        Ok("分组显示状态已更新".to_string())
-    } else {
-        Err("分组不存在".to_string())
-    }
+    Ok("分组显示状态已更新".to_string())
 }
 
 #[tauri::command]
-pub async fn add_new_app(app_handle: AppHandle, app: AppData) -> Result<String, String> {
-    let mut storage = load_app_data()?;
-    let previous_storage = storage.clone();
-    let mut app = app;
-    normalize_app_categories(&mut app);
-    validate_project_hotkey(&storage, &mut app)?;
-    if app.usage_count.is_none() {
-        app.usage_count = Some(0);
-    }
-    storage.apps.push(app);
-    save_app_data(storage.apps, storage.categories, storage.selected_category)?;
+pub async fn add_new_app(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    app: AppData,
+) -> Result<String, String> {
+    let settings = state.store.settings().ok();
+    let ((), entry_id) = state
+        .store
+        .update_library_revertible("添加项目", |storage| {
+            let mut app = app;
+            normalize_app_categories(&mut app);
+            validate_launch_args(&app)?;
+            validate_project_hotkey(storage, settings.as_ref(), &mut app)?;
+            if app.usage_count.is_none() {
+                app.usage_count = Some(0);
+            }
+            storage.apps.push(app);
+            Ok(())
+        })?;
     if let Err(error) = crate::system_integration::refresh_global_shortcuts(&app_handle) {
         return Err(rollback_shortcut_registration(
             &app_handle,
-            previous_storage,
+            &state.store,
+            entry_id,
             error,
         ));
     }
@@ -506,139 +896,361 @@ pub async fn add_new_app(app_handle: AppHandle, app: AppData) -> Result<String,
 }
 
 #[tauri::command]
-pub async fn update_app(app_handle: AppHandle, app: AppData) -> Result<String, String> {
-    let mut storage = load_app_data()?;
-    let previous_storage = storage.clone();
-    let mut app = app;
-    normalize_app_categories(&mut app);
-    validate_project_hotkey(&storage, &mut app)?;
-
-    if let Some(existing_app) = storage.apps.iter_mut().find(|a| a.id == app.id) {
-        let usage_count = app.usage_count.or(existing_app.usage_count).or(Some(0));
-        let last_launched_at = app.last_launched_at.or(existing_app.last_launched_at);
-        *existing_app = app;
-        existing_app.usage_count = usage_count;
-        existing_app.last_launched_at = last_launched_at;
-        save_app_data(storage.apps, storage.categories, storage.selected_category)?;
-        if let Err(error) = crate::system_integration::refresh_global_shortcuts(&app_handle) {
-            return Err(rollback_shortcut_registration(
-                &app_handle,
-                previous_storage,
-                error,
-            ));
-        }
-        Ok("应用更新成功".to_string())
-    } else {
-        Err("应用不存在".to_string())
+pub async fn update_app(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    app: AppData,
+) -> Result<String, String> {
+    let settings = state.store.settings().ok();
+    let ((), entry_id) = state
+        .store
+        .update_library_revertible("编辑项目", |storage| {
+            let mut app = app;
+            let existing_index = storage
+                .apps
+                .iter()
+                .position(|a| a.id == app.id)
+                .ok_or("应用不存在")?;
+            if app.category_orders.is_empty() {
+                // 编辑窗口不携带分组排序时沿用已有的排序
+                app.category_orders = storage.apps[existing_index].category_orders.clone();
+            }
+            normalize_app_categories(&mut app);
+            validate_launch_args(&app)?;
+            validate_project_hotkey(storage, settings.as_ref(), &mut app)?;
+
+            let existing_app = &mut storage.apps[existing_index];
+            let usage_count = app.usage_count.or(existing_app.usage_count).or(Some(0));
+            let last_launched_at = app.last_launched_at.or(existing_app.last_launched_at);
+            *existing_app = app;
+            existing_app.usage_count = usage_count;
+            existing_app.last_launched_at = last_launched_at;
+            Ok(())
+        })?;
+    if let Err(error) = crate::system_integration::refresh_global_shortcuts(&app_handle) {
+        return Err(rollback_shortcut_registration(
+            &app_handle,
+            &state.store,
+            entry_id,
+            error,
+        ));
     }
+    Ok("应用更新成功".to_string())
 }
 
 #[tauri::command]
-pub fn increment_app_usage(app_id: i64) -> Result<serde_json::Value, String> {
-    let mut storage = load_app_data()?;
-
-    if let Some(app) = storage.apps.iter_mut().find(|app| app.id == app_id) {
+pub fn increment_app_usage(
+    state: State<'_, AppState>,
+    app_id: i64,
+) -> Result<serde_json::Value, String> {
+    let launch_time = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_err(|e| format!("获取启动时间失败: {}", e))?
+        .as_secs() as i64;
+
+    let next_count = state.store.update_storage(|storage| {
+        let app = storage
+            .apps
+            .iter_mut()
+            .find(|app| app.id == app_id)
+            .ok_or("应用不存在")?;
         let next_count = app.usage_count.unwrap_or(0).saturating_add(1);
-        let launch_time = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map_err(|e| format!("获取启动时间失败: {}", e))?
-            .as_secs() as i64;
         app.usage_count = Some(next_count);
         app.last_launched_at = Some(launch_time);
-        save_app_data(storage.apps, storage.categories, storage.selected_category)?;
-        Ok(serde_json::json!({
-            "usage_count": next_count,
-            "last_launched_at": launch_time
-        }))
-    } else {
-        Err("应用不存在".to_string())
-    }
+        Ok(next_count)
+    })?;
+
+    Ok(serde_json::json!({
+        "usage_count": next_count,
+        "last_launched_at": launch_time
+    }))
 }
 
 #[tauri::command]
-pub async fn get_app_by_id(app_id: i64) -> Result<AppData, String> {
-    let storage = load_app_data()?;
-
-    if let Some(app) = storage.apps.iter().find(|a| a.id == app_id) {
-        Ok(app.clone())
-    } else {
-        Err("应用不存在".to_string())
-    }
+pub async fn get_app_by_id(state: State<'_, AppState>, app_id: i64) -> Result<AppData, String> {
+    state
+        .store
+        .with_storage(|storage| storage.apps.iter().find(|a| a.id == app_id).cloned())?
+        .ok_or_else(|| "应用不存在".to_string())
 }
 
 // export/import/clear functions proxied to be used by UI
 #[tauri::command]
-pub fn export_app_data_to_file(file_path: String) -> Result<String, String> {
-    let storage = load_app_data()?;
-    let settings = load_app_settings()?;
+pub fn export_app_data_to_file(
+    state: State<'_, AppState>,
+    file_path: String,
+    scope: Option<DataScope>,
+) -> Result<String, String> {
+    let scope = scope.unwrap_or_default();
+
+    let storage = match &scope {
+        DataScope::All | DataScope::Library => Some(state.store.storage()?),
+        DataScope::Categories { category_ids } => Some(
+            state
+                .store
+                .with_storage(|storage| select_categories(storage, category_ids))??,
+        ),
+        DataScope::Settings => None,
+    };
+    let settings = if scope.includes_settings() {
+        Some(state.store.settings()?)
+    } else {
+        None
+    };
+
+    // 扩展名为 .zip 时导出为压缩归档，图标单独存放
+    let export_path = Path::new(&file_path);
+    if is_archive_path(export_path) {
+        write_archive(
+            export_path,
+            storage.as_ref(),
+            settings.as_ref(),
+            serde_json::json!({ "backup_type": "export", "scope": scope }),
+        )?;
+        return Ok("数据导出成功".to_string());
+    }
 
-    let export_data = serde_json::json!({
-        "storage": storage,
-        "settings": settings,
+    let mut export_data = serde_json::json!({
+        "scope": scope,
         "export_time": std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_secs(),
         "version": env!("CARGO_PKG_VERSION")
     });
+    if let Some(storage) = storage {
+        export_data["storage"] = serde_json::json!(storage);
+    }
+    if let Some(settings) = settings {
+        export_data["settings"] = serde_json::json!(settings);
+    }
 
     let json_data = serde_json::to_string_pretty(&export_data)
         .map_err(|e| format!("序列化导出数据失败: {}", e))?;
-    fs::write(&file_path, json_data).map_err(|e| format!("写入导出文件失败: {}", e))?;
+    write_file_atomic(Path::new(&file_path), json_data.as_bytes())
+        .map_err(|e| format!("写入导出文件失败: {}", e))?;
 
     Ok("数据导出成功".to_string())
 }
 
-#[tauri::command]
-pub fn import_app_data_from_file(file_path: String) -> Result<String, String> {
-    let json_data =
-        fs::read_to_string(&file_path).map_err(|e| format!("读取导入文件失败: {}", e))?;
+/// 导出文件的内容，应用数据和设置都已迁移到当前版本
+pub struct ImportFile {
+    pub scope: DataScope,
+    pub storage: Option<AppStorage>,
+    pub settings: Option<AppSettings>,
+}
+
+/// 读取导出文件，支持压缩归档（包括自动备份归档）和 JSON 文件
+pub fn read_import_file(file_path: &str) -> Result<ImportFile, String> {
+    let json_data = fs::read(file_path).map_err(|e| format!("读取导入文件失败: {}", e))?;
+
+    if is_archive(&json_data) {
+        let archive = read_archive(&json_data)?;
+        let scope = match archive.manifest.get("scope") {
+            Some(scope) => serde_json::from_value(scope.clone())
+                .map_err(|e| format!("解析导入范围失败: {}", e))?,
+            None => DataScope::All,
+        };
+        let storage = archive.storage.map(|mut storage| {
+            for app in &mut storage.apps {
+                normalize_app_categories(app);
+            }
+            storage
+        });
+        return Ok(ImportFile {
+            scope,
+            storage,
+            settings: archive.settings,
+        });
+    }
 
     let import_data: Value =
-        serde_json::from_str(&json_data).map_err(|e| format!("解析导入数据失败: {}", e))?;
+        serde_json::from_slice(&json_data).map_err(|e| format!("解析导入数据失败: {}", e))?;
+
+    // 旧版本的导出文件没有 scope 字段，包含全部数据
+    let scope = match import_data.get("scope") {
+        Some(scope) => {
+            serde_json::from_value(scope.clone()).map_err(|e| format!("解析导入范围失败: {}", e))?
+        }
+        None => DataScope::All,
+    };
+
+    let storage = match import_data.get("storage") {
+        Some(storage_data) => {
+            let mut storage = parse_storage_value(storage_data.clone())?;
+            for app in &mut storage.apps {
+                normalize_app_categories(app);
+            }
+            Some(storage)
+        }
+        None => None,
+    };
+
+    let settings = match import_data.get("settings") {
+        Some(settings_data) => Some(parse_settings_value(settings_data.clone())?),
+        None => None,
+    };
 
-    if let Some(storage_data) = import_data.get("storage") {
-        let storage: AppStorage = serde_json::from_value(storage_data.clone())
-            .map_err(|e| format!("解析存储数据失败: {}", e))?;
-        save_app_data(storage.apps, storage.categories, storage.selected_category)?;
+    Ok(ImportFile {
+        scope,
+        storage,
+        settings,
+    })
+}
+
+/// 从导出文件导入数据
+///
+/// 未指定范围时按文件自身的范围导入。整体导入会替换应用数据和设置；
+/// 分组导入会把所选分组及其中的项目合并到现有数据中，冲突按 `policy`
+/// 处理（默认保留本地内容），不会修改设置。
+#[tauri::command]
+pub fn import_app_data_from_file(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    file_path: String,
+    scope: Option<DataScope>,
+    policy: Option<ConflictPolicy>,
+) -> Result<String, String> {
+    let import_file = read_import_file(&file_path)?;
+    let scope = scope.unwrap_or_else(|| import_file.scope.clone());
+
+    let is_partial_file = matches!(import_file.scope, DataScope::Categories { .. });
+    if is_partial_file && matches!(scope, DataScope::All | DataScope::Library) {
+        return Err("该文件只包含部分分组，只能按分组导入".to_string());
+    }
+
+    let storage = if scope.includes_library() {
+        Some(import_file.storage.ok_or("导入文件中没有应用数据")?)
+    } else {
+        None
+    };
+    let settings = if scope.includes_settings() {
+        import_file.settings
+    } else {
+        None
+    };
+    if scope == DataScope::Settings && settings.is_none() {
+        return Err("导入文件中没有设置数据".to_string());
+    }
+
+    let entry_id = match (&scope, storage) {
+        (DataScope::Categories { category_ids }, Some(imported)) => {
+            let selected = select_categories(&imported, category_ids)?;
+            validate_category_ids(&selected.categories)?;
+            validate_apps_launch_args(&selected.apps)?;
+            let settings = state.store.settings().ok();
+            let policy = policy.unwrap_or(ConflictPolicy::KeepMine);
+            let ((), entry_id) =
+                state
+                    .store
+                    .update_library_revertible("导入分组", |storage| {
+                        merge_storage(storage, &selected, settings.as_ref(), policy);
+                        Ok(())
+                    })?;
+            entry_id
+        }
+        (_, Some(imported)) => {
+            validate_category_ids(&imported.categories)?;
+            validate_apps_launch_args(&imported.apps)?;
+            state.store.replace_library("导入数据", imported)?
+        }
+        (_, None) => None,
+    };
+
+    // 导入的设置保留本地的备份记录，快捷键注册失败时连同设置一起恢复
+    let previous_settings = match &settings {
+        Some(_) => state.store.settings().ok(),
+        None => None,
+    };
+    if let Some(mut settings) = settings {
+        if let Some(previous_settings) = &previous_settings {
+            keep_backup_bookkeeping(&mut settings, previous_settings);
+        }
+        apply_auto_start(&settings)?;
+        if let Err(error) = state.store.replace_settings(settings) {
+            if let Some(previous_settings) = &previous_settings {
+                let _ = apply_auto_start(previous_settings);
+            }
+            return Err(error);
+        }
+        BackupManager::restart_scheduler(&app);
     }
 
-    if let Some(settings_data) = import_data.get("settings") {
-        let settings: AppSettings = serde_json::from_value(settings_data.clone())
-            .map_err(|e| format!("解析设置数据失败: {}", e))?;
-        save_app_settings(settings)?;
+    if let Err(mut error) = crate::system_integration::refresh_global_shortcuts(&app) {
+        if entry_id.is_none() && previous_settings.is_none() {
+            return Err(format!("数据已导入，但快捷键注册失败: {}", error));
+        }
+        if let Some(previous_settings) = previous_settings {
+            let _ = apply_auto_start(&previous_settings);
+            match state.store.replace_settings(previous_settings) {
+                Ok(()) => BackupManager::restart_scheduler(&app),
+                Err(restore_error) => {
+                    error = format!("{}；恢复设置失败: {}", error, restore_error);
+                }
+            }
+        }
+        return Err(rollback_shortcut_registration(
+            &app,
+            &state.store,
+            entry_id,
+            error,
+        ));
     }
 
     Ok("数据导入成功".to_string())
 }
 
 #[tauri::command]
-pub fn clear_all_data() -> Result<String, String> {
+pub fn clear_all_data(app: AppHandle, state: State<'_, AppState>) -> Result<String, String> {
     let empty_storage = AppStorage {
+        schema_version: STORAGE_SCHEMA_VERSION,
         apps: vec![],
         categories: vec![],
         selected_category: Some("all".to_string()),
+        trash: vec![],
     };
-    save_app_data(
-        empty_storage.apps,
-        empty_storage.categories,
-        empty_storage.selected_category,
-    )?;
+    state.store.replace_library("清空数据", empty_storage)?;
+    let previous_settings = state.store.settings().ok();
     let default_settings = get_default_settings();
-    save_app_settings(default_settings)?;
+    apply_auto_start(&default_settings)?;
+    if let Err(error) = state.store.replace_settings(default_settings) {
+        if let Some(previous_settings) = &previous_settings {
+            let _ = apply_auto_start(previous_settings);
+        }
+        return Err(error);
+    }
+    BackupManager::restart_scheduler(&app);
     Ok("所有数据已清空".to_string())
 }
 
+/// 保存项目排序
+///
+/// 指定 `category_id` 时把 `order` 写入项目在该分组中的排序，
+/// 否则写入"全部应用"的排序。
 #[tauri::command]
-pub fn save_apps_order(apps: Vec<AppData>) -> Result<String, String> {
-    let mut storage = load_app_data()?;
-
-    for updated_app in apps {
-        if let Some(existing_app) = storage.apps.iter_mut().find(|a| a.id == updated_app.id) {
-            existing_app.order = updated_app.order;
+pub fn save_apps_order(
+    state: State<'_, AppState>,
+    apps: Vec<AppData>,
+    category_id: Option<String>,
+) -> Result<String, String> {
+    let category_id = category_id.filter(|category_id| category_id != "all");
+    state.store.update_library("调整项目顺序", |storage| {
+        for updated_app in apps {
+            if let Some(existing_app) = storage.apps.iter_mut().find(|a| a.id == updated_app.id) {
+                match (&category_id, updated_app.order) {
+                    (None, order) => existing_app.order = order,
+                    (Some(category_id), Some(order))
+                        if existing_app.category_ids.contains(category_id) =>
+                    {
+                        existing_app
+                            .category_orders
+                            .insert(category_id.clone(), order);
+                    }
+                    _ => {}
+                }
+            }
         }
-    }
-
-    save_app_data(storage.apps, storage.categories, storage.selected_category)?;
+        Ok(())
+    })?;
     Ok("排序保存成功".to_string())
 }
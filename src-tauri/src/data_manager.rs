@@ -3,30 +3,35 @@
 //! 此模块负责处理应用数据的导入、导出和重置功能。
 //! 提供了用户界面友好的数据备份和恢复操作。
 
+use tauri::{AppHandle, State};
+
 use crate::data;
+use crate::models::AppState;
+#[cfg(target_os = "windows")]
 use crate::system::open_file_dialog;
 
 /// 导出数据到用户选择的文件
 ///
 /// 此函数会打开一个文件保存对话框，让用户选择导出位置，
-/// 然后将应用数据和设置导出为 JSON 格式。
+/// 然后将应用数据和设置导出为压缩归档（默认）或 JSON 格式，由文件扩展名决定。
 #[tauri::command]
-pub fn export_data() -> Result<String, String> {
+pub fn export_data(state: State<'_, AppState>) -> Result<String, String> {
     // 打开文件保存对话框
     #[cfg(target_os = "windows")]
     {
         let filters = vec![
+            ("备份归档".to_string(), vec!["zip".to_string()]),
             ("JSON文件".to_string(), vec!["json".to_string()]),
             ("所有文件".to_string(), vec!["*".to_string()]),
         ];
         let file_name = format!(
-            "lora_backup_{}.json",
+            "lora_backup_{}.zip",
             chrono::Local::now().format("%Y%m%d_%H%M%S")
         );
         let file_path =
-            crate::win_native::save_file_dialog("导出数据", &filters, "json", &file_name)?;
+            crate::win_native::save_file_dialog("导出数据", &filters, "zip", &file_name)?;
 
-        data::export_app_data_to_file(file_path)
+        data::export_app_data_to_file(state, file_path, None)
     }
 
     #[cfg(not(target_os = "windows"))]
@@ -34,14 +39,14 @@ pub fn export_data() -> Result<String, String> {
         // 其他平台使用默认路径
         let data_dir = data::get_app_data_dir()?;
         let file_path = data_dir.join(format!(
-            "lora_backup_{}.json",
+            "lora_backup_{}.zip",
             std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap()
                 .as_secs()
         ));
 
-        data::export_app_data_to_file(file_path.to_string_lossy().to_string())
+        data::export_app_data_to_file(state, file_path.to_string_lossy().to_string(), None)
     }
 }
 
@@ -50,22 +55,26 @@ pub fn export_data() -> Result<String, String> {
 /// 此函数会打开一个文件选择对话框，让用户选择要导入的数据文件，
 /// 然后将文件中的数据和设置导入到应用中。
 #[tauri::command]
-pub fn import_data() -> Result<String, String> {
+pub fn import_data(app: AppHandle, state: State<'_, AppState>) -> Result<String, String> {
     // 打开文件选择对话框
     #[cfg(target_os = "windows")]
     {
         // 使用已经实现的open_file_dialog函数
         let filters = vec![
-            ("JSON文件".to_string(), vec!["json".to_string()]),
+            (
+                "Lora 数据文件".to_string(),
+                vec!["zip".to_string(), "json".to_string()],
+            ),
             ("所有文件".to_string(), vec!["*".to_string()]),
         ];
 
         let file_path = open_file_dialog("选择要导入的数据文件".to_string(), filters)?;
-        data::import_app_data_from_file(file_path)
+        data::import_app_data_from_file(app, state, file_path, None, None)
     }
 
     #[cfg(not(target_os = "windows"))]
     {
+        let _ = (app, state);
         Err("当前平台不支持文件导入对话框".to_string())
     }
 }
@@ -75,6 +84,6 @@ pub fn import_data() -> Result<String, String> {
 /// 此函数会清除所有应用数据和设置，恢复到初始状态。
 /// 这是一个不可逆操作，请谨慎使用。
 #[tauri::command]
-pub fn reset_data() -> Result<String, String> {
-    data::clear_all_data()
+pub fn reset_data(app: AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    data::clear_all_data(app, state)
 }
@@ -0,0 +1,94 @@
+//! 导入导出范围模块
+//!
+//! 导出和导入可以只处理部分数据：
+//! - 全部（应用数据和设置）
+//! - 仅设置
+//! - 仅应用数据
+//! - 指定的分组及其中的项目，用于把某个分组分享给他人而不影响对方的设置
+
+use serde::{Deserialize, Serialize};
+
+use crate::data::normalize_app_categories;
+use crate::models::AppStorage;
+
+/// 导入导出的数据范围
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DataScope {
+    #[default]
+    All,
+    Settings,
+    Library,
+    Categories {
+        category_ids: Vec<String>,
+    },
+}
+
+impl DataScope {
+    /// 是否包含设置
+    pub fn includes_settings(&self) -> bool {
+        matches!(self, DataScope::All | DataScope::Settings)
+    }
+
+    /// 是否包含应用数据（全部或部分分组）
+    pub fn includes_library(&self) -> bool {
+        !matches!(self, DataScope::Settings)
+    }
+}
+
+/// 从应用数据中取出指定的分组及其中的项目
+///
+/// 项目只保留对所选分组的归属；"全部应用"不是实际的分组，会被忽略。
+/// 分组列表为空时取出全部分组。回收站和当前选中的分组不会被包含。
+pub fn select_categories(
+    storage: &AppStorage,
+    category_ids: &[String],
+) -> Result<AppStorage, String> {
+    for category_id in category_ids {
+        if category_id != "all"
+            && !storage
+                .categories
+                .iter()
+                .any(|category| &category.id == category_id)
+        {
+            return Err(format!("分组不存在: {}", category_id));
+        }
+    }
+
+    let selected = |category_id: &String| {
+        category_id != "all" && (category_ids.is_empty() || category_ids.contains(category_id))
+    };
+
+    let categories: Vec<_> = storage
+        .categories
+        .iter()
+        .filter(|category| selected(&category.id))
+        .cloned()
+        .collect();
+    if categories.is_empty() {
+        return Err("没有选择任何分组".to_string());
+    }
+
+    let apps = storage
+        .apps
+        .iter()
+        .filter(|app| app.category_ids.iter().any(selected))
+        .map(|app| {
+            let mut app = app.clone();
+            app.category_ids.retain(selected);
+            app.category_orders
+                .retain(|category_id, _| selected(category_id));
+            app.category.clear();
+            normalize_app_categories(&mut app);
+            app
+        })
+        .collect();
+
+    Ok(AppStorage {
+        schema_version: storage.schema_version,
+        apps,
+        categories,
+        selected_category: None,
+        trash: vec![],
+    })
+}
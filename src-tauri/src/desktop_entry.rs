@@ -0,0 +1,966 @@
+//! freedesktop 桌面项（.desktop 文件）模块
+//!
+//! 按照 Desktop Entry 规范解析 `[Desktop Entry]` 组：
+//! - `Name` 按当前语言环境选择本地化名称（`Name[zh_CN]`、`Name[zh]` 等）
+//! - `Exec` 按规范拆分引号和转义，展开字段代码：启动时不传文件，`%f/%F/%u/%U`
+//!   直接去掉；`%i`、`%c`、`%k` 替换为图标、名称和文件路径；已废弃的代码也去掉
+//! - `Icon`、`Path`、`Terminal`，以及 `Type=Link` 的 `URL`
+//! - 列出应用时使用的 `NoDisplay`、`Hidden`、`OnlyShowIn`、`NotShowIn`、`TryExec`
+//!
+//! 开机自启动通过 XDG 自启动目录（~/.config/autostart）中的 lora.desktop 实现。
+//!
+//! 从 .desktop 文件创建项目时，程序路径、`Exec` 中的参数和目标类型分别写入项目的
+//! `target_path`、`launch_args` 和 `target_type`。与 Windows 的 .lnk 一样，项目启动时
+//! 直接启动 .desktop 文件本身，使用项目的启动参数，这样工作目录和终端设置不会丢失。
+
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::data::write_file_atomic;
+use crate::linux_native::{
+    elevated_command, find_program, is_executable, open_command, terminal_command,
+};
+
+const DESKTOP_ENTRY_GROUP: &str = "[Desktop Entry]";
+/// 自启动项的文件名
+const AUTOSTART_FILE_NAME: &str = "lora.desktop";
+
+/// 按优先级查找主题图标时使用的尺寸目录
+const ICON_SIZES: [&str; 7] = [
+    "256x256", "128x128", "96x96", "64x64", "48x48", "32x32", "scalable",
+];
+
+/// 解析后的桌面项
+#[derive(Debug, Clone, Default)]
+pub struct DesktopEntry {
+    /// .desktop 文件路径
+    pub file_path: PathBuf,
+    /// `Application` 或 `Link`
+    pub entry_type: String,
+    /// 本地化后的名称
+    pub name: String,
+    /// 程序和参数，字段代码已展开
+    pub exec: Vec<String>,
+    pub icon: Option<String>,
+    /// 工作目录（`Path` 键）
+    pub working_dir: Option<String>,
+    pub terminal: bool,
+    /// `Type=Link` 的网址
+    pub url: Option<String>,
+    pub no_display: bool,
+    /// 为 true 时视为已删除，同时隐藏优先级更低的同名桌面项
+    pub hidden: bool,
+    /// 只在这些桌面环境中显示
+    pub only_show_in: Vec<String>,
+    /// 不在这些桌面环境中显示
+    pub not_show_in: Vec<String>,
+    /// 用于判断程序是否已安装
+    pub try_exec: Option<String>,
+    /// 自启动项是否启用（`X-GNOME-Autostart-enabled`，默认启用）
+    pub autostart_enabled: bool,
+}
+
+/// 判断文件是否为 .desktop 文件
+pub fn is_desktop_entry_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("desktop"))
+}
+
+/// 还原字符串值中的转义字符（`\s`、`\n`、`\t`、`\r`、`\\`）
+fn unescape_value(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            result.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => result.push(' '),
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some('\\') => result.push('\\'),
+            // 其它转义（例如 Exec 中的 `\"`）留给后续处理
+            Some(other) => {
+                result.push('\\');
+                result.push(other);
+            }
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// 按规范拆分 `Exec` 值，返回 (参数, 是否带引号)
+fn split_exec(exec: &str) -> Result<Vec<(String, bool)>, String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+    let mut has_token = false;
+    let mut chars = exec.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => {
+                in_quotes = !in_quotes;
+                quoted = true;
+                has_token = true;
+            }
+            // 引号内 `\"`、`` \` ``、`\$`、`\\` 表示字符本身
+            '\\' if in_quotes => match chars.next() {
+                Some(next @ ('"' | '`' | '$' | '\\')) => current.push(next),
+                Some(next) => {
+                    current.push('\\');
+                    current.push(next);
+                }
+                None => return Err("Exec 以未完成的转义结尾".to_string()),
+            },
+            ' ' | '\t' if !in_quotes => {
+                if has_token {
+                    args.push((std::mem::take(&mut current), quoted));
+                }
+                quoted = false;
+                has_token = false;
+            }
+            _ => {
+                current.push(ch);
+                has_token = true;
+            }
+        }
+    }
+    if in_quotes {
+        return Err("Exec 中的引号没有闭合".to_string());
+    }
+    if has_token {
+        args.push((current, quoted));
+    }
+    Ok(args)
+}
+
+/// 展开参数中的字段代码
+fn expand_field_codes(args: Vec<(String, bool)>, entry: &DesktopEntry) -> Vec<String> {
+    let mut expanded = Vec::new();
+
+    for (arg, quoted) in args {
+        if !quoted {
+            match arg.as_str() {
+                "%f" | "%F" | "%u" | "%U" => continue,
+                "%i" => {
+                    if let Some(icon) = &entry.icon {
+                        expanded.push("--icon".to_string());
+                        expanded.push(icon.clone());
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        let mut result = String::with_capacity(arg.len());
+        let mut chars = arg.chars();
+        while let Some(ch) = chars.next() {
+            if ch != '%' {
+                result.push(ch);
+                continue;
+            }
+            match chars.next() {
+                Some('%') => result.push('%'),
+                Some('c') => result.push_str(&entry.name),
+                Some('k') => result.push_str(&entry.file_path.to_string_lossy()),
+                // 文件参数、已废弃或不认识的字段代码直接去掉
+                _ => {}
+            }
+        }
+        // 只由字段代码组成的参数展开后为空，整个去掉
+        if result.is_empty() && !quoted && !arg.is_empty() {
+            continue;
+        }
+        expanded.push(result);
+    }
+    expanded
+}
+
+/// 当前语言环境下查找本地化键时依次尝试的后缀
+///
+/// 对于 `zh_CN.UTF-8@mod`，依次为 `zh_CN@mod`、`zh_CN`、`zh@mod`、`zh`。
+fn locale_candidates() -> Vec<String> {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default();
+
+    let (locale, modifier) = match locale.split_once('@') {
+        Some((locale, modifier)) => (locale, Some(modifier)),
+        None => (locale.as_str(), None),
+    };
+    let locale = locale.split('.').next().unwrap_or_default();
+    if locale.is_empty() || locale == "C" || locale == "POSIX" {
+        return Vec::new();
+    }
+    let (lang, country) = match locale.split_once('_') {
+        Some((lang, country)) => (lang, Some(country)),
+        None => (locale, None),
+    };
+
+    let mut candidates = Vec::new();
+    if let Some(country) = country {
+        if let Some(modifier) = modifier {
+            candidates.push(format!("{}_{}@{}", lang, country, modifier));
+        }
+        candidates.push(format!("{}_{}", lang, country));
+    }
+    if let Some(modifier) = modifier {
+        candidates.push(format!("{}@{}", lang, modifier));
+    }
+    candidates.push(lang.to_string());
+    candidates
+}
+
+impl DesktopEntry {
+    /// 读取并解析 .desktop 文件
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("读取桌面项文件失败: {}", e))?;
+        Self::parse(&content, path)
+    }
+
+    /// 解析 .desktop 文件内容
+    pub fn parse(content: &str, file_path: &Path) -> Result<Self, String> {
+        let mut values: HashMap<&str, &str> = HashMap::new();
+        let mut in_entry_group = false;
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line.starts_with('[') {
+                in_entry_group = line == DESKTOP_ENTRY_GROUP;
+                continue;
+            }
+            if !in_entry_group {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once('=') {
+                // 重复的键以第一次出现的为准
+                values.entry(key.trim()).or_insert(value.trim());
+            }
+        }
+        if values.is_empty() {
+            return Err("不是有效的桌面项文件".to_string());
+        }
+
+        let localized = |key: &str| {
+            locale_candidates()
+                .iter()
+                .find_map(|locale| values.get(format!("{}[{}]", key, locale).as_str()))
+                .or_else(|| values.get(key))
+                .map(|value| unescape_value(value))
+        };
+        let flag = |key: &str| values.get(key).is_some_and(|value| *value == "true");
+        let list = |key: &str| {
+            values
+                .get(key)
+                .map(|value| {
+                    value
+                        .split(';')
+                        .filter(|item| !item.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        let mut entry = DesktopEntry {
+            file_path: file_path.to_path_buf(),
+            entry_type: values
+                .get("Type")
+                .map(|value| value.to_string())
+                .unwrap_or_else(|| "Application".to_string()),
+            name: localized("Name").ok_or("桌面项缺少 Name")?,
+            icon: localized("Icon").filter(|icon| !icon.is_empty()),
+            working_dir: values
+                .get("Path")
+                .map(|value| unescape_value(value))
+                .filter(|dir| !dir.is_empty()),
+            terminal: flag("Terminal"),
+            url: values.get("URL").map(|value| unescape_value(value)),
+            no_display: flag("NoDisplay"),
+            hidden: flag("Hidden"),
+            only_show_in: list("OnlyShowIn"),
+            not_show_in: list("NotShowIn"),
+            try_exec: values
+                .get("TryExec")
+                .map(|value| unescape_value(value))
+                .filter(|value| !value.is_empty()),
+            autostart_enabled: values.get("X-GNOME-Autostart-enabled") != Some(&"false"),
+            ..Default::default()
+        };
+
+        match entry.entry_type.as_str() {
+            "Application" => {
+                let exec = values.get("Exec").ok_or("桌面项缺少 Exec")?;
+                let args = split_exec(&unescape_value(exec))?;
+                entry.exec = expand_field_codes(args, &entry);
+                if entry.exec.is_empty() {
+                    return Err("桌面项的 Exec 为空".to_string());
+                }
+            }
+            "Link" => {
+                if entry.url.as_deref().unwrap_or_default().is_empty() {
+                    return Err("桌面项缺少 URL".to_string());
+                }
+            }
+            other => return Err(format!("不支持的桌面项类型: {}", other)),
+        }
+        Ok(entry)
+    }
+
+    pub fn is_link(&self) -> bool {
+        self.entry_type == "Link"
+    }
+
+    /// 程序的完整路径，`Exec` 中是程序名时在 PATH 中查找
+    pub fn program_path(&self) -> Option<PathBuf> {
+        let program = Path::new(self.exec.first()?);
+        if program.is_absolute() {
+            Some(program.to_path_buf())
+        } else {
+            find_program(&program.to_string_lossy())
+        }
+    }
+
+    /// 启动目标：`Type=Link` 为网址，否则为程序路径
+    pub fn target(&self) -> Option<String> {
+        if self.is_link() {
+            return self.url.clone();
+        }
+        self.program_path()
+            .map(|path| path.to_string_lossy().to_string())
+    }
+
+    /// `Exec` 中程序之后的参数
+    pub fn exec_args(&self) -> &[String] {
+        self.exec.get(1..).unwrap_or_default()
+    }
+
+    /// 程序路径和启动参数，`launch_args` 为项目的启动参数，不为空时代替 `Exec` 中的参数
+    fn program_and_args<'a>(
+        &'a self,
+        launch_args: &'a [String],
+    ) -> Result<(PathBuf, &'a [String]), String> {
+        let program = self
+            .program_path()
+            .ok_or_else(|| format!("找不到程序: {}", self.exec[0]))?;
+        let args = if launch_args.is_empty() {
+            self.exec_args()
+        } else {
+            launch_args
+        };
+        Ok((program, args))
+    }
+
+    /// 构建启动命令
+    pub fn command(&self, launch_args: &[String]) -> Result<Command, String> {
+        if self.is_link() {
+            return Ok(open_command(self.url.as_deref().unwrap_or_default()));
+        }
+
+        let (program, args) = self.program_and_args(launch_args)?;
+        let mut command = if self.terminal {
+            terminal_command(&program, args)?
+        } else {
+            let mut command = Command::new(&program);
+            command.args(args);
+            command
+        };
+        match &self.working_dir {
+            Some(dir) => command.current_dir(dir),
+            None => command.current_dir(dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"))),
+        };
+        Ok(command)
+    }
+
+    /// 构建以管理员权限启动的命令，链接仍按普通方式打开
+    pub fn elevated_command(&self, launch_args: &[String]) -> Result<Command, String> {
+        if self.is_link() {
+            return self.command(launch_args);
+        }
+        let (program, args) = self.program_and_args(launch_args)?;
+        elevated_command(&program, args)
+    }
+
+    /// 是否应在应用列表中显示
+    ///
+    /// `current_desktops` 为 `$XDG_CURRENT_DESKTOP` 中列出的桌面环境。
+    pub fn should_show(&self, current_desktops: &[String]) -> bool {
+        if self.no_display || self.hidden {
+            return false;
+        }
+        let in_current = |desktops: &[String]| {
+            desktops
+                .iter()
+                .any(|desktop| current_desktops.contains(desktop))
+        };
+        if !self.only_show_in.is_empty() && !in_current(&self.only_show_in) {
+            return false;
+        }
+        if in_current(&self.not_show_in) {
+            return false;
+        }
+        // TryExec 指定的程序不存在说明应用没有安装
+        match self.try_exec.as_deref() {
+            Some(try_exec) if Path::new(try_exec).is_absolute() => {
+                is_executable(Path::new(try_exec))
+            }
+            Some(try_exec) => find_program(try_exec).is_some(),
+            None => true,
+        }
+    }
+
+    /// 把名称、目标、启动参数和目标类型写入 `get_file_info` 的结果
+    pub fn apply_to_file_info(&self, mut info: Value) -> Value {
+        info["name"] = Value::from(self.name.clone());
+        info["target_path"] = self.target().map(Value::from).unwrap_or(Value::Null);
+        info["launch_args"] = Value::from(join_args(self.exec_args()));
+        info["target_type"] = Value::from(if self.is_link() { "url" } else { "file" });
+        info
+    }
+}
+
+/// 把参数拼接为启动参数字符串，包含空白或引号的参数加上双引号
+///
+/// 引号内的 `"` 和 `\` 加反斜杠转义，结果可以由 `command_line::split_args` 还原。
+pub fn join_args(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| {
+            if !arg.is_empty()
+                && !arg.contains(|ch: char| {
+                    ch.is_whitespace() || ch == '"' || ch == '\'' || ch == '\\'
+                })
+            {
+                return arg.clone();
+            }
+            let escaped = arg.replace('\\', "\\\\").replace('"', "\\\"");
+            format!("\"{}\"", escaped)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// XDG 数据目录：`$XDG_DATA_HOME`（默认 ~/.local/share）和 `$XDG_DATA_DIRS`
+/// （默认 /usr/local/share 和 /usr/share），按优先级排列
+pub fn xdg_data_dirs() -> Vec<PathBuf> {
+    let mut data_dirs = Vec::new();
+    match std::env::var_os("XDG_DATA_HOME").filter(|value| !value.is_empty()) {
+        Some(data_home) => data_dirs.push(PathBuf::from(data_home)),
+        None => data_dirs.extend(dirs::home_dir().map(|home| home.join(".local/share"))),
+    }
+    match std::env::var_os("XDG_DATA_DIRS").filter(|value| !value.is_empty()) {
+        Some(paths) => data_dirs.extend(std::env::split_paths(&paths)),
+        None => data_dirs.extend(["/usr/local/share", "/usr/share"].map(PathBuf::from)),
+    }
+    data_dirs
+}
+
+/// 查找图标文件
+///
+/// `Icon` 为绝对路径时直接使用；否则在各数据目录的 hicolor 主题和 pixmaps 中查找
+/// PNG 或 SVG 图标，优先使用较大的尺寸。
+pub fn find_icon(icon: &str) -> Option<PathBuf> {
+    let icon_path = Path::new(icon);
+    if icon_path.is_absolute() {
+        return icon_path.is_file().then(|| icon_path.to_path_buf());
+    }
+
+    let data_dirs = xdg_data_dirs();
+    let mut candidates = Vec::new();
+    for size in ICON_SIZES {
+        for data_dir in &data_dirs {
+            for extension in ["png", "svg"] {
+                candidates.push(
+                    data_dir
+                        .join("icons/hicolor")
+                        .join(size)
+                        .join("apps")
+                        .join(format!("{}.{}", icon, extension)),
+                );
+            }
+        }
+    }
+    for data_dir in &data_dirs {
+        for extension in ["png", "svg"] {
+            candidates.push(
+                data_dir
+                    .join("pixmaps")
+                    .join(format!("{}.{}", icon, extension)),
+            );
+        }
+    }
+    candidates.into_iter().find(|candidate| candidate.is_file())
+}
+
+/// 读取桌面项的图标并转换为 data URL
+pub fn icon_data_url(entry: &DesktopEntry) -> Option<String> {
+    use base64::engine::general_purpose;
+    use base64::Engine;
+
+    let icon_path = find_icon(entry.icon.as_deref()?)?;
+    let mime = match icon_path
+        .extension()
+        .and_then(|extension| extension.to_str())
+    {
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        _ => return None,
+    };
+    let bytes = fs::read(icon_path).ok()?;
+    Some(format!(
+        "data:{};base64,{}",
+        mime,
+        general_purpose::STANDARD.encode(bytes)
+    ))
+}
+
+/// 递归收集应用目录中的 .desktop 文件，返回 (桌面项 id, 路径)
+///
+/// 桌面项 id 为相对于应用目录的路径，其中的 `/` 替换为 `-`，例如 `kde4/foo.desktop`
+/// 的 id 为 `kde4-foo.desktop`。
+fn collect_desktop_files(root: &Path, dir: &Path, files: &mut Vec<(String, PathBuf)>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_desktop_files(root, &path, files);
+            continue;
+        }
+        if !is_desktop_entry_path(&path) {
+            continue;
+        }
+        if let Ok(relative) = path.strip_prefix(root) {
+            let desktop_id = relative.to_string_lossy().replace('/', "-");
+            files.push((desktop_id, path));
+        }
+    }
+}
+
+/// 列出已安装的应用，返回与开始菜单项目相同结构的 JSON
+///
+/// 依次扫描 `$XDG_DATA_HOME/applications` 和各 `$XDG_DATA_DIRS/applications`。
+/// 同一个桌面项 id 只使用优先级最高的文件，即使该文件被隐藏（用户以此删除系统应用）。
+pub fn list_applications() -> Vec<Value> {
+    let current_desktops: Vec<String> = std::env::var("XDG_CURRENT_DESKTOP")
+        .unwrap_or_default()
+        .split(':')
+        .filter(|desktop| !desktop.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    let mut seen_ids = HashSet::new();
+    let mut items = Vec::new();
+    for data_dir in xdg_data_dirs() {
+        let root = data_dir.join("applications");
+        let mut files = Vec::new();
+        collect_desktop_files(&root, &root, &mut files);
+        files.sort();
+
+        for (desktop_id, path) in files {
+            if !seen_ids.insert(desktop_id) {
+                continue;
+            }
+            let Ok(entry) = DesktopEntry::load(&path) else {
+                continue;
+            };
+            if !entry.should_show(&current_desktops) {
+                continue;
+            }
+
+            let file_path = path.to_string_lossy().to_string();
+            let icon = entry
+                .icon
+                .as_deref()
+                .and_then(find_icon)
+                .map(|icon_path| icon_path.to_string_lossy().to_string())
+                .unwrap_or_default();
+            // 与 Windows 的 .lnk 一样直接启动 .desktop 文件，启动参数为空时使用其中的参数
+            items.push(json!({
+                "id": file_path,
+                "name": entry.name,
+                "icon": icon,
+                "path": file_path,
+                "target_path": entry.target(),
+                "target_type": if entry.is_link() { "url" } else { "file" },
+                "launch_args": ""
+            }));
+        }
+    }
+    items
+}
+
+/// 按规范把参数写成 `Exec` 中的一项
+///
+/// 包含保留字符的参数加上双引号，引号内的 `"`、`` ` ``、`$`、`\` 加反斜杠转义；
+/// 字符串值本身的转义规则还要求反斜杠再转义一次。
+fn quote_exec_arg(arg: &str) -> String {
+    let arg = arg.replace('%', "%%");
+    let needs_quotes = arg.is_empty()
+        || arg
+            .chars()
+            .any(|ch| ch.is_whitespace() || "\"'\\><~|&;$*?#()`".contains(ch));
+    let arg = if needs_quotes {
+        let mut quoted = String::from('"');
+        for ch in arg.chars() {
+            if matches!(ch, '"' | '`' | '$' | '\\') {
+                quoted.push('\\');
+            }
+            quoted.push(ch);
+        }
+        quoted.push('"');
+        quoted
+    } else {
+        arg
+    };
+    arg.replace('\\', "\\\\")
+}
+
+/// 自启动项路径：`$XDG_CONFIG_HOME/autostart/lora.desktop`（默认 ~/.config/autostart）
+fn autostart_entry_path() -> Result<PathBuf, String> {
+    let config_dir = dirs::config_dir().ok_or("无法获取配置目录")?;
+    Ok(config_dir.join("autostart").join(AUTOSTART_FILE_NAME))
+}
+
+/// 写入或删除自启动项，`minimized_arg` 不为空时追加到启动命令中
+pub fn set_auto_start(
+    exe_path: &Path,
+    enable: bool,
+    minimized_arg: Option<&str>,
+) -> Result<(), String> {
+    let entry_path = autostart_entry_path()?;
+    if !enable {
+        return match fs::remove_file(&entry_path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("删除自启动项失败: {}", e)),
+        };
+    }
+
+    let mut exec = quote_exec_arg(&exe_path.to_string_lossy());
+    if let Some(arg) = minimized_arg {
+        exec.push(' ');
+        exec.push_str(&quote_exec_arg(arg));
+    }
+    let content = format!(
+        "[Desktop Entry]\nType=Application\nName=Lora\nExec={}\nTerminal=false\nX-GNOME-Autostart-enabled=true\n",
+        exec
+    );
+
+    if let Some(autostart_dir) = entry_path.parent() {
+        fs::create_dir_all(autostart_dir).map_err(|e| format!("创建自启动目录失败: {}", e))?;
+    }
+    write_file_atomic(&entry_path, content.as_bytes())
+        .map_err(|e| format!("写入自启动项失败: {}", e))
+}
+
+/// 读取自启动项，判断开机自启动是否启用
+///
+/// 用户在桌面环境中关闭自启动时会设置 `Hidden=true` 或 `X-GNOME-Autostart-enabled=false`。
+pub fn is_auto_start_enabled() -> Result<bool, String> {
+    let entry_path = autostart_entry_path()?;
+    if !entry_path.exists() {
+        return Ok(false);
+    }
+    let entry = DesktopEntry::load(&entry_path)?;
+    Ok(!entry.hidden && entry.autostart_enabled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_line::split_args;
+    use crate::test_support::{with_env, TestDir};
+    use std::ffi::OsStr;
+    use std::os::unix::fs::PermissionsExt;
+
+    /// 在固定的语言环境下解析，`locale` 为 `None` 时不使用本地化名称
+    fn parse_in(locale: Option<&str>, content: &str) -> Result<DesktopEntry, String> {
+        let vars = [
+            ("LC_ALL", locale.map(OsStr::new)),
+            ("LC_MESSAGES", None),
+            ("LANG", None),
+        ];
+        with_env(&vars, || {
+            DesktopEntry::parse(content, Path::new("/usr/share/applications/test.desktop"))
+        })
+    }
+
+    fn parse(content: &str) -> DesktopEntry {
+        parse_in(None, content).unwrap()
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_application_entry() {
+        let entry = parse(
+            "# 注释\n\
+             [Desktop Entry]\n\
+             Type=Application\n\
+             Name=Text Editor\n\
+             Name=Duplicate\n\
+             Exec=/usr/bin/editor --new-window \"My Notes\" %U\n\
+             Icon=editor\n\
+             Path=/home/user/work\\sdir\n\
+             Terminal=true\n\
+             NoDisplay=false\n\
+             OnlyShowIn=GNOME;KDE;\n\
+             NotShowIn=XFCE\n\
+             TryExec=editor\n\
+             \n\
+             [Desktop Action new]\n\
+             Name=New Window\n\
+             Exec=/usr/bin/other\n",
+        );
+
+        assert_eq!(entry.entry_type, "Application");
+        assert_eq!(entry.name, "Text Editor");
+        assert_eq!(
+            entry.exec,
+            strings(&["/usr/bin/editor", "--new-window", "My Notes"])
+        );
+        assert_eq!(entry.exec_args(), strings(&["--new-window", "My Notes"]));
+        assert_eq!(entry.icon.as_deref(), Some("editor"));
+        assert_eq!(entry.working_dir.as_deref(), Some("/home/user/work dir"));
+        assert!(entry.terminal);
+        assert!(!entry.no_display);
+        assert_eq!(entry.only_show_in, strings(&["GNOME", "KDE"]));
+        assert_eq!(entry.not_show_in, strings(&["XFCE"]));
+        assert_eq!(entry.try_exec.as_deref(), Some("editor"));
+        assert!(entry.autostart_enabled);
+        assert_eq!(entry.target().as_deref(), Some("/usr/bin/editor"));
+    }
+
+    #[test]
+    fn parses_link_entry() {
+        let entry = parse(
+            "[Desktop Entry]\n\
+             Type=Link\n\
+             Name=Project Docs\n\
+             Icon=text-html\n\
+             URL=https://example.com/docs?page=1\n",
+        );
+
+        assert!(entry.is_link());
+        assert!(entry.exec.is_empty());
+        assert_eq!(
+            entry.target().as_deref(),
+            Some("https://example.com/docs?page=1")
+        );
+
+        let info = entry.apply_to_file_info(json!({ "name": "docs.desktop" }));
+        assert_eq!(info["name"], "Project Docs");
+        assert_eq!(info["target_path"], "https://example.com/docs?page=1");
+        assert_eq!(info["target_type"], "url");
+        assert_eq!(info["launch_args"], "");
+    }
+
+    #[test]
+    fn rejects_invalid_entries() {
+        for content in [
+            "",
+            "[Other Group]\nName=App\nExec=app\n",
+            "[Desktop Entry]\nExec=app\n",
+            "[Desktop Entry]\nName=App\n",
+            "[Desktop Entry]\nName=App\nExec=%f\n",
+            "[Desktop Entry]\nName=App\nExec=\"app\n",
+            "[Desktop Entry]\nType=Link\nName=Site\n",
+            "[Desktop Entry]\nType=Directory\nName=Folder\n",
+        ] {
+            assert!(parse_in(None, content).is_err(), "应当拒绝: {:?}", content);
+        }
+    }
+
+    #[test]
+    fn expands_field_codes() {
+        let entry = parse(
+            "[Desktop Entry]\n\
+             Name=Viewer\n\
+             Icon=viewer-icon\n\
+             Exec=viewer %f %F %u %U %i --title=%c --from=%k 100%% %d \"%%f\"\n",
+        );
+
+        assert_eq!(
+            entry.exec,
+            strings(&[
+                "viewer",
+                "--icon",
+                "viewer-icon",
+                "--title=Viewer",
+                "--from=/usr/share/applications/test.desktop",
+                "100%",
+                "%f",
+            ])
+        );
+
+        let without_icon = parse("[Desktop Entry]\nName=Viewer\nExec=viewer %i %c\n");
+        assert_eq!(without_icon.exec, strings(&["viewer", "Viewer"]));
+    }
+
+    #[test]
+    fn unescapes_string_values() {
+        assert_eq!(unescape_value(r"a\sb\tc\nd\re\\f"), "a b\tc\nd\re\\f");
+        assert_eq!(unescape_value(r#"keep \" and \$"#), r#"keep \" and \$"#);
+        assert_eq!(unescape_value("trailing\\"), "trailing\\");
+    }
+
+    #[test]
+    fn quoted_exec_args_round_trip() {
+        let args = strings(&[
+            "/opt/My App/bin/app",
+            "--plain",
+            "",
+            "say \"hi\"",
+            "$HOME",
+            "`cmd`",
+            r"C:\path\to",
+            "100%",
+            "it's",
+            "a;b&c|d",
+        ]);
+        let exec = args
+            .iter()
+            .map(|arg| quote_exec_arg(arg))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let entry = parse(&format!("[Desktop Entry]\nName=App\nExec={}\n", exec));
+        assert_eq!(entry.exec, args);
+    }
+
+    #[test]
+    fn joined_args_round_trip() {
+        let args = strings(&[
+            "plain",
+            "with space",
+            "",
+            "say \"hi\"",
+            r"back\slash",
+            "it's",
+            "tab\tchar",
+            "$HOME",
+        ]);
+
+        assert_eq!(split_args(&join_args(&args)).unwrap(), args);
+        assert_eq!(join_args(&strings(&["-a", "b"])), "-a b");
+    }
+
+    #[test]
+    fn locale_candidates_follow_the_spec_order() {
+        let candidates = |locale: &str| {
+            with_env(
+                &[
+                    ("LC_ALL", Some(OsStr::new(locale))),
+                    ("LC_MESSAGES", None),
+                    ("LANG", None),
+                ],
+                locale_candidates,
+            )
+        };
+
+        assert_eq!(
+            candidates("zh_CN.UTF-8@mod"),
+            strings(&["zh_CN@mod", "zh_CN", "zh@mod", "zh"])
+        );
+        assert_eq!(candidates("de_DE.UTF-8"), strings(&["de_DE", "de"]));
+        assert_eq!(candidates("fr"), strings(&["fr"]));
+        assert!(candidates("C").is_empty());
+        assert!(candidates("POSIX").is_empty());
+    }
+
+    #[test]
+    fn uses_localized_name() {
+        let content = "[Desktop Entry]\n\
+                       Name=Files\n\
+                       Name[zh]=文件\n\
+                       Name[zh_CN]=文件管理器\n\
+                       Exec=files\n";
+
+        let name = |locale| parse_in(Some(locale), content).unwrap().name;
+        assert_eq!(name("zh_CN.UTF-8"), "文件管理器");
+        assert_eq!(name("zh_TW.UTF-8"), "文件");
+        assert_eq!(name("en_US.UTF-8"), "Files");
+    }
+
+    #[test]
+    fn should_show_respects_visibility_keys() {
+        let dir = TestDir::new("desktop_entry_try_exec");
+        let program = dir.join("installed");
+        fs::write(&program, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&program, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let gnome = strings(&["GNOME"]);
+        let entry = |extra: &str| parse(&format!("[Desktop Entry]\nName=App\nExec=app\n{}", extra));
+
+        assert!(entry("").should_show(&gnome));
+        assert!(!entry("NoDisplay=true\n").should_show(&gnome));
+        assert!(!entry("Hidden=true\n").should_show(&gnome));
+        assert!(entry("OnlyShowIn=KDE;GNOME;\n").should_show(&gnome));
+        assert!(!entry("OnlyShowIn=KDE;\n").should_show(&gnome));
+        assert!(!entry("NotShowIn=GNOME;\n").should_show(&gnome));
+        assert!(entry("NotShowIn=KDE;\n").should_show(&gnome));
+        assert!(entry(&format!("TryExec={}\n", program.display())).should_show(&gnome));
+        assert!(
+            !entry(&format!("TryExec={}\n", dir.join("missing").display())).should_show(&gnome)
+        );
+    }
+
+    #[test]
+    fn higher_priority_entries_shadow_the_same_id() {
+        let data_home = TestDir::new("desktop_entry_data_home");
+        let data_dir = TestDir::new("desktop_entry_data_dir");
+        let write = |root: &Path, relative: &str, content: &str| {
+            let path = root.join("applications").join(relative);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        let app = |name: &str| format!("[Desktop Entry]\nName={}\nExec=/bin/true\n", name);
+
+        // 用户目录中隐藏的桌面项会隐藏系统中的同名应用
+        write(
+            &data_home,
+            "removed.desktop",
+            &format!("{}Hidden=true\n", app("Removed")),
+        );
+        write(&data_dir, "removed.desktop", &app("System Removed"));
+        // 子目录中的桌面项 id 为 kde4-editor.desktop，被用户目录中的同名文件覆盖
+        write(&data_home, "kde4-editor.desktop", &app("User Editor"));
+        write(&data_dir, "kde4/editor.desktop", &app("System Editor"));
+        write(&data_dir, "viewer.desktop", &app("Viewer"));
+
+        let names: Vec<String> = with_env(
+            &[
+                ("XDG_DATA_HOME", Some(data_home.as_os_str())),
+                ("XDG_DATA_DIRS", Some(data_dir.as_os_str())),
+                ("XDG_CURRENT_DESKTOP", Some(OsStr::new("GNOME"))),
+                ("LC_ALL", None),
+                ("LC_MESSAGES", None),
+                ("LANG", None),
+            ],
+            list_applications,
+        )
+        .iter()
+        .map(|item| item["name"].as_str().unwrap().to_string())
+        .collect();
+
+        assert_eq!(names, strings(&["User Editor", "Viewer"]));
+    }
+}
@@ -0,0 +1,140 @@
+//! 数据变更通知模块
+//!
+//! Store 每次提交修改后都会计算修改前后的差异，并向所有窗口（主窗口、设置、
+//! 新建项目、编辑项目、托盘菜单）广播带类型的事件。窗口可以根据事件内容
+//! 增量更新自身状态，而不必重新加载全部数据。
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Emitter};
+
+use crate::item_diff::diff_items;
+use crate::models::{AppData, AppSettings, AppStorage, CategoryData};
+
+/// 应用数据变更事件名
+pub const LIBRARY_CHANGED_EVENT: &str = "library-changed";
+/// 设置变更事件名
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+
+/// 应用数据变更内容
+///
+/// 新增和修改的项目、分组携带完整的新数据，删除的只携带 id。
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct LibraryChange {
+    pub added_apps: Vec<AppData>,
+    pub updated_apps: Vec<AppData>,
+    pub removed_app_ids: Vec<i64>,
+    pub added_categories: Vec<CategoryData>,
+    pub updated_categories: Vec<CategoryData>,
+    pub removed_category_ids: Vec<String>,
+    /// 分组列表的顺序是否发生变化
+    pub categories_reordered: bool,
+    /// 当前选中分组发生变化时为 true，此时 selected_category 为新值
+    pub selected_category_changed: bool,
+    pub selected_category: Option<String>,
+    /// 回收站内容是否发生变化
+    pub trash_changed: bool,
+}
+
+impl LibraryChange {
+    pub fn is_empty(&self) -> bool {
+        self.added_apps.is_empty()
+            && self.updated_apps.is_empty()
+            && self.removed_app_ids.is_empty()
+            && self.added_categories.is_empty()
+            && self.updated_categories.is_empty()
+            && self.removed_category_ids.is_empty()
+            && !self.categories_reordered
+            && !self.selected_category_changed
+            && !self.trash_changed
+    }
+}
+
+/// 设置变更内容，`changes` 为发生变化的设置项及其新值
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct SettingsChange {
+    pub changed_keys: Vec<String>,
+    pub changes: Map<String, Value>,
+}
+
+/// 计算两份应用数据之间的差异
+pub fn diff_storage(previous: Option<&AppStorage>, current: &AppStorage) -> LibraryChange {
+    let empty_apps: Vec<AppData> = Vec::new();
+    let empty_categories: Vec<CategoryData> = Vec::new();
+    let previous_apps = previous.map_or(&empty_apps, |storage| &storage.apps);
+    let previous_categories = previous.map_or(&empty_categories, |storage| &storage.categories);
+
+    let apps = diff_items(previous_apps, &current.apps);
+    let categories = diff_items(previous_categories, &current.categories);
+    let mut change = LibraryChange {
+        added_apps: apps.added.into_iter().cloned().collect(),
+        updated_apps: apps
+            .modified
+            .into_iter()
+            .map(|(_, app)| app.clone())
+            .collect(),
+        removed_app_ids: apps.removed.into_iter().map(|app| app.id).collect(),
+        added_categories: categories.added.into_iter().cloned().collect(),
+        updated_categories: categories
+            .modified
+            .into_iter()
+            .map(|(_, category)| category.clone())
+            .collect(),
+        removed_category_ids: categories
+            .removed
+            .into_iter()
+            .map(|category| category.id.clone())
+            .collect(),
+        categories_reordered: categories.reordered,
+        ..LibraryChange::default()
+    };
+
+    change.trash_changed = previous.map_or(&[][..], |storage| &storage.trash) != current.trash;
+
+    if previous.map(|storage| &storage.selected_category) != Some(&current.selected_category) {
+        change.selected_category_changed = true;
+        change.selected_category = current.selected_category.clone();
+    }
+
+    change
+}
+
+/// 计算两份设置之间发生变化的设置项
+pub fn diff_settings(previous: Option<&AppSettings>, current: &AppSettings) -> SettingsChange {
+    let previous_value = previous
+        .and_then(|settings| serde_json::to_value(settings).ok())
+        .unwrap_or(Value::Null);
+    let Ok(Value::Object(current_map)) = serde_json::to_value(current) else {
+        return SettingsChange::default();
+    };
+
+    let mut change = SettingsChange::default();
+    for (key, value) in current_map {
+        if previous_value.get(&key) != Some(&value) {
+            change.changed_keys.push(key.clone());
+            change.changes.insert(key, value);
+        }
+    }
+
+    change
+}
+
+/// 向所有窗口广播应用数据变更
+pub fn emit_library_change(app: &AppHandle, change: &LibraryChange) {
+    if change.is_empty() {
+        return;
+    }
+    if let Err(error) = app.emit(LIBRARY_CHANGED_EVENT, change) {
+        eprintln!("广播应用数据变更失败: {}", error);
+    }
+}
+
+/// 向所有窗口广播设置变更
+pub fn emit_settings_change(app: &AppHandle, change: &SettingsChange) {
+    if change.changed_keys.is_empty() {
+        return;
+    }
+    if let Err(error) = app.emit(SETTINGS_CHANGED_EVENT, change) {
+        eprintln!("广播设置变更失败: {}", error);
+    }
+}
@@ -1,4 +1,5 @@
 use base64::Engine as _Base64Engine;
+#[cfg(target_os = "windows")]
 use std::fs;
 use std::path::Path;
 use std::time::Duration;
@@ -49,8 +50,22 @@ pub fn resolve_shortcut_target(shortcut_path: &str) -> Option<String> {
         Some(shortcut_path.to_string())
     }
 
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(target_os = "linux")]
     {
+        let path = Path::new(shortcut_path);
+        if !crate::desktop_entry::is_desktop_entry_path(path) {
+            return None;
+        }
+
+        // 解析 .desktop 文件，应用返回程序路径，链接返回网址；无法解析时没有目标
+        crate::desktop_entry::DesktopEntry::load(path)
+            .ok()
+            .and_then(|entry| entry.target())
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        let _ = shortcut_path;
         None
     }
 }
@@ -0,0 +1,255 @@
+//! 撤销/重做模块
+//!
+//! 修改应用数据的命令（添加、编辑、删除项目，调整排序，分组操作，导入等）
+//! 会在 Store 中记录一条可逆的历史记录。记录只保存发生变化的项目、分组和
+//! 回收站条目修改前后的内容以及列表顺序，而不是整份数据的快照。
+//!
+//! 历史记录只保存在内存中，数量有上限，重启后清空。
+
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use tauri::{AppHandle, State};
+
+use crate::item_diff::{diff_items, Keyed};
+use crate::models::{AppData, AppState, AppStorage, CategoryData, TrashEntry};
+
+/// 最多保留的撤销记录数量
+const MAX_HISTORY_ENTRIES: usize = 50;
+
+/// 一个列表的变化：发生变化的数据项修改前后的内容，以及修改前后的顺序
+#[derive(Debug, Clone)]
+struct ItemChanges<T: Keyed> {
+    changes: Vec<(Option<T>, Option<T>)>,
+    before_order: Vec<T::Key>,
+    after_order: Vec<T::Key>,
+}
+
+impl<T: Keyed + Clone + PartialEq> ItemChanges<T> {
+    fn between(before: &[T], after: &[T]) -> Self {
+        let diff = diff_items(before, after);
+        let added = diff
+            .added
+            .into_iter()
+            .map(|item| (None, Some(item.clone())));
+        let modified = diff
+            .modified
+            .into_iter()
+            .map(|(previous, item)| (Some(previous.clone()), Some(item.clone())));
+        let removed = diff
+            .removed
+            .into_iter()
+            .map(|item| (Some(item.clone()), None));
+
+        Self {
+            changes: added.chain(modified).chain(removed).collect(),
+            before_order: before.iter().map(Keyed::key).collect(),
+            after_order: after.iter().map(Keyed::key).collect(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.changes.is_empty() && self.before_order == self.after_order
+    }
+
+    /// 把列表从一侧的状态切换到另一侧，`forward` 为 true 时重做，否则撤销
+    fn apply(&self, items: &mut Vec<T>, forward: bool) {
+        let mut items_by_key: HashMap<T::Key, T> =
+            items.drain(..).map(|item| (item.key(), item)).collect();
+        for (before, after) in &self.changes {
+            let (from, to) = if forward {
+                (before, after)
+            } else {
+                (after, before)
+            };
+            let current = from
+                .as_ref()
+                .and_then(|from| items_by_key.remove(&from.key()));
+            if let Some(to) = to {
+                let mut to = to.clone();
+                // 使用统计在记录之后仍会变化，撤销编辑时不应回退
+                if let Some(current) = &current {
+                    to.copy_usage_from(current);
+                }
+                items_by_key.insert(to.key(), to);
+            }
+        }
+
+        let order = if forward {
+            &self.after_order
+        } else {
+            &self.before_order
+        };
+        items.extend(order.iter().filter_map(|key| items_by_key.remove(key)));
+        // 记录之后新增、不在历史顺序中的数据项保留在末尾
+        items.extend(items_by_key.into_values());
+    }
+}
+
+/// 一次可撤销的应用数据修改
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    // 记录到 History 时分配，用于回滚指定的修改
+    id: u64,
+    label: String,
+    apps: ItemChanges<AppData>,
+    categories: ItemChanges<CategoryData>,
+    trash: ItemChanges<TrashEntry>,
+    selected_category: (Option<String>, Option<String>),
+}
+
+impl HistoryEntry {
+    /// 记录两份应用数据之间的差异，没有变化时返回 None
+    pub fn between(label: &str, before: &AppStorage, after: &AppStorage) -> Option<Self> {
+        let entry = Self {
+            id: 0,
+            label: label.to_string(),
+            apps: ItemChanges::between(&before.apps, &after.apps),
+            categories: ItemChanges::between(&before.categories, &after.categories),
+            trash: ItemChanges::between(&before.trash, &after.trash),
+            selected_category: (
+                before.selected_category.clone(),
+                after.selected_category.clone(),
+            ),
+        };
+
+        let unchanged = entry.apps.is_empty()
+            && entry.categories.is_empty()
+            && entry.trash.is_empty()
+            && entry.selected_category.0 == entry.selected_category.1;
+        (!unchanged).then_some(entry)
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// 在应用数据上应用本次修改，`forward` 为 true 时重做，否则撤销
+    pub fn apply(&self, storage: &mut AppStorage, forward: bool) {
+        self.apps.apply(&mut storage.apps, forward);
+        self.categories.apply(&mut storage.categories, forward);
+        self.trash.apply(&mut storage.trash, forward);
+        storage.selected_category = if forward {
+            self.selected_category.1.clone()
+        } else {
+            self.selected_category.0.clone()
+        };
+    }
+}
+
+/// 有上限的撤销/重做记录
+#[derive(Debug, Default)]
+pub struct History {
+    undo: VecDeque<HistoryEntry>,
+    redo: Vec<HistoryEntry>,
+    next_id: u64,
+}
+
+impl History {
+    /// 记录一次新的修改，同时清空重做记录，返回记录的 id
+    pub fn record(&mut self, mut entry: HistoryEntry) -> u64 {
+        self.next_id += 1;
+        entry.id = self.next_id;
+        self.redo.clear();
+        self.undo.push_back(entry);
+        while self.undo.len() > MAX_HISTORY_ENTRIES {
+            self.undo.pop_front();
+        }
+        self.next_id
+    }
+
+    /// 按 id 取出一条记录，用于修改被回滚的情况
+    ///
+    /// 同时返回记录是否已经被撤销（位于重做列表中）。
+    pub fn take(&mut self, id: u64) -> Option<(HistoryEntry, bool)> {
+        if let Some(index) = self.undo.iter().position(|entry| entry.id == id) {
+            return self.undo.remove(index).map(|entry| (entry, false));
+        }
+        let index = self.redo.iter().position(|entry| entry.id == id)?;
+        Some((self.redo.remove(index), true))
+    }
+
+    pub fn pop_undo(&mut self) -> Option<HistoryEntry> {
+        self.undo.pop_back()
+    }
+
+    pub fn pop_redo(&mut self) -> Option<HistoryEntry> {
+        self.redo.pop()
+    }
+
+    /// 撤销完成后把记录放入重做列表
+    pub fn push_redo(&mut self, entry: HistoryEntry) {
+        self.redo.push(entry);
+    }
+
+    /// 重做完成或撤销失败后把记录放回撤销列表，不清空重做记录
+    pub fn push_undo(&mut self, entry: HistoryEntry) {
+        self.undo.push_back(entry);
+    }
+
+    pub fn status(&self) -> HistoryStatus {
+        HistoryStatus {
+            can_undo: !self.undo.is_empty(),
+            can_redo: !self.redo.is_empty(),
+            undo_label: self.undo.back().map(|entry| entry.label.clone()),
+            redo_label: self.redo.last().map(|entry| entry.label.clone()),
+        }
+    }
+}
+
+/// 撤销/重做状态，供界面显示按钮和提示
+#[derive(Serialize, Clone, Debug)]
+pub struct HistoryStatus {
+    pub can_undo: bool,
+    pub can_redo: bool,
+    pub undo_label: Option<String>,
+    pub redo_label: Option<String>,
+}
+
+/// 撤销或重做后重新注册全局快捷键，失败时反向执行一次以恢复原状
+fn step_history(app: &AppHandle, state: &AppState, forward: bool) -> Result<HistoryStatus, String> {
+    let store = &state.store;
+    let (label, entry_id) = if forward {
+        store.redo_storage()?.ok_or("没有可重做的操作")?
+    } else {
+        store.undo_storage()?.ok_or("没有可撤销的操作")?
+    };
+
+    if let Err(error) = crate::system_integration::refresh_global_shortcuts(app) {
+        if let Err(revert_error) = store.revert_history_step(entry_id, forward) {
+            return Err(format!("{}；恢复数据失败: {}", error, revert_error));
+        }
+        let _ = crate::system_integration::refresh_global_shortcuts(app);
+        let action = if forward { "重做" } else { "撤销" };
+        return Err(format!("{}“{}”失败: {}", action, label, error));
+    }
+
+    Ok(store.history_status())
+}
+
+/// 撤销最近一次应用数据修改
+#[tauri::command]
+pub fn undo_library_change(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<HistoryStatus, String> {
+    step_history(&app, &state, false)
+}
+
+/// 重做最近一次被撤销的应用数据修改
+#[tauri::command]
+pub fn redo_library_change(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<HistoryStatus, String> {
+    step_history(&app, &state, true)
+}
+
+/// 获取撤销/重做状态
+#[tauri::command]
+pub fn get_history_status(state: State<'_, AppState>) -> HistoryStatus {
+    state.store.history_status()
+}
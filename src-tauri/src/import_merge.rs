@@ -0,0 +1,645 @@
+//! 合并导入模块
+//!
+//! 把导出文件中的项目和分组合并到现有数据中，而不是整体替换：
+//! - 项目先按 id、再按规范化后的路径匹配；分组先按 id、再按名称匹配
+//! - 匹配到但内容不同的视为冲突，按选择的策略处理（保留本地、使用导入、两者都保留）
+//! - 新增的项目排在“全部应用”和所属分组的末尾
+//! - 预览命令只返回计划的新增、更新和冲突，不写入任何数据
+//!
+//! 合并导入只处理应用数据，不会修改本地设置。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{AppHandle, State};
+
+use crate::category_manager::{app_order_in_category, generate_category_id, validate_category_ids};
+use crate::data::{
+    normalize_app_categories, read_import_file, rollback_shortcut_registration,
+    validate_apps_launch_args, validate_project_hotkey,
+};
+use crate::item_diff::{changed_fields, Keyed};
+use crate::models::{AppData, AppSettings, AppState, AppStorage, CategoryData};
+
+/// 冲突处理策略
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// 保留本地内容，忽略导入的版本
+    KeepMine,
+    /// 使用导入的内容覆盖本地版本
+    TakeTheirs,
+    /// 两者都保留，导入的版本作为新条目添加
+    KeepBoth,
+}
+
+/// 冲突的处理结果
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    KeptMine,
+    TookTheirs,
+    KeptBoth,
+}
+
+/// 一处冲突：本地与导入的条目匹配，但内容不同
+#[derive(Serialize, Clone, Debug)]
+pub struct ImportConflict {
+    pub kind: String, // "app" 或 "category"
+    pub local_id: String,
+    pub imported_id: String,
+    pub name: String,
+    pub matched_by: String, // "id"、"path" 或 "name"
+    pub changed_fields: Vec<String>,
+    pub resolution: ConflictResolution,
+}
+
+/// 合并计划，预览和实际导入返回相同的结构
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct ImportPlan {
+    pub added_apps: Vec<AppData>,
+    pub updated_apps: Vec<AppData>,
+    pub unchanged_app_count: usize,
+    pub added_categories: Vec<CategoryData>,
+    pub updated_categories: Vec<CategoryData>,
+    pub unchanged_category_count: usize,
+    pub conflicts: Vec<ImportConflict>,
+    pub warnings: Vec<String>,
+}
+
+/// 规范化路径用于匹配：统一分隔符、去掉末尾分隔符，Windows 上忽略大小写
+fn normalize_path(path: &str) -> String {
+    let path = path.trim().replace('\\', "/");
+    let path = path.trim_end_matches('/');
+    if cfg!(target_os = "windows") {
+        path.to_lowercase()
+    } else {
+        path.to_string()
+    }
+}
+
+fn normalize_name(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// 比较分组时忽略的字段，id 和排序属于本地状态
+const IGNORED_CATEGORY_FIELDS: &[&str] = &["id", "is_default", "order"];
+
+/// 比较项目时忽略的字段：id、旧版的单一分组字段、排序和使用统计属于本地状态
+const IGNORED_APP_FIELDS: &[&str] = &[
+    "id",
+    "category",
+    "order",
+    "category_orders",
+    "usage_count",
+    "last_launched_at",
+];
+
+fn resolution_for(policy: ConflictPolicy) -> ConflictResolution {
+    match policy {
+        ConflictPolicy::KeepMine => ConflictResolution::KeptMine,
+        ConflictPolicy::TakeTheirs => ConflictResolution::TookTheirs,
+        ConflictPolicy::KeepBoth => ConflictResolution::KeptBoth,
+    }
+}
+
+/// 把新增的项目排到“全部应用”和所属各分组的末尾，避免与本地项目的排序重复
+fn append_app_orders(storage: &AppStorage, app: &mut AppData) {
+    let next_order = |category_id: &str| {
+        storage
+            .apps
+            .iter()
+            .filter_map(|app| app_order_in_category(app, category_id))
+            .max()
+            .map_or(0, |order| order + 1)
+    };
+
+    app.order = Some(next_order("all"));
+    app.category_orders = app
+        .category_ids
+        .iter()
+        .filter(|category_id| *category_id != "all")
+        .map(|category_id| (category_id.clone(), next_order(category_id)))
+        .collect();
+}
+
+/// 生成不与现有项目冲突的新项目 id
+fn next_app_id(storage: &AppStorage) -> i64 {
+    let max_id = storage.apps.iter().map(|app| app.id).max().unwrap_or(0);
+    chrono::Utc::now().timestamp_millis().max(max_id + 1)
+}
+
+/// 合并分组，返回导入分组 id 到本地分组 id 的映射
+fn merge_categories(
+    storage: &mut AppStorage,
+    imported: &[CategoryData],
+    policy: ConflictPolicy,
+    plan: &mut ImportPlan,
+) -> HashMap<String, String> {
+    let mut id_map = HashMap::new();
+
+    for imported_category in imported {
+        let matched = storage
+            .categories
+            .iter()
+            .position(|category| category.id == imported_category.id)
+            .map(|index| (index, "id"))
+            .or_else(|| {
+                storage
+                    .categories
+                    .iter()
+                    .position(|category| {
+                        normalize_name(&category.name) == normalize_name(&imported_category.name)
+                    })
+                    .map(|index| (index, "name"))
+            });
+
+        let Some((index, matched_by)) = matched else {
+            let mut category = imported_category.clone();
+            category.order = storage
+                .categories
+                .iter()
+                .filter_map(|category| category.order)
+                .max()
+                .map(|order| order + 1);
+            id_map.insert(imported_category.id.clone(), category.id.clone());
+            storage.categories.push(category.clone());
+            plan.added_categories.push(category);
+            continue;
+        };
+
+        let local = &storage.categories[index];
+        let changed_fields = changed_fields(local, imported_category, IGNORED_CATEGORY_FIELDS);
+        if changed_fields.is_empty() || local.id == "all" || local.is_default {
+            id_map.insert(imported_category.id.clone(), local.id.clone());
+            plan.unchanged_category_count += 1;
+            continue;
+        }
+
+        plan.conflicts.push(ImportConflict {
+            kind: "category".to_string(),
+            local_id: local.id.clone(),
+            imported_id: imported_category.id.clone(),
+            name: local.name.clone(),
+            matched_by: matched_by.to_string(),
+            changed_fields,
+            resolution: resolution_for(policy),
+        });
+
+        match policy {
+            ConflictPolicy::KeepMine => {
+                id_map.insert(imported_category.id.clone(), local.id.clone());
+            }
+            ConflictPolicy::TakeTheirs => {
+                let local = &mut storage.categories[index];
+                local.name = imported_category.name.clone();
+                local.icon = imported_category.icon.clone();
+                local.hidden = imported_category.hidden;
+                id_map.insert(imported_category.id.clone(), local.id.clone());
+                plan.updated_categories.push(local.clone());
+            }
+            ConflictPolicy::KeepBoth => {
+                let mut category = imported_category.clone();
+                category.id = generate_category_id(storage);
+                category.is_default = false;
+                if storage.categories.iter().any(|existing| {
+                    normalize_name(&existing.name) == normalize_name(&category.name)
+                }) {
+                    category.name = format!("{} (导入)", category.name);
+                }
+                category.order = storage
+                    .categories
+                    .iter()
+                    .filter_map(|category| category.order)
+                    .max()
+                    .map(|order| order + 1);
+                id_map.insert(imported_category.id.clone(), category.id.clone());
+                storage.categories.push(category.clone());
+                plan.added_categories.push(category);
+            }
+        }
+    }
+
+    id_map
+}
+
+/// 把导入项目的分组 id 换成本地分组 id，并去掉本地不存在的分组
+fn translate_app_categories(
+    app: &mut AppData,
+    id_map: &HashMap<String, String>,
+    categories: &[CategoryData],
+) {
+    app.category_ids = app
+        .category_ids
+        .iter()
+        .filter_map(|category_id| {
+            let category_id = id_map.get(category_id).unwrap_or(category_id);
+            let exists = category_id == "all"
+                || categories
+                    .iter()
+                    .any(|category| &category.id == category_id);
+            exists.then(|| category_id.clone())
+        })
+        .collect();
+    app.category_orders = std::mem::take(&mut app.category_orders)
+        .into_iter()
+        .filter_map(|(category_id, order)| Some((id_map.get(&category_id)?.clone(), order)))
+        .collect();
+    app.category.clear();
+    normalize_app_categories(app);
+}
+
+/// 校验快捷键，冲突时清除导入项目的快捷键并记录警告
+fn clear_conflicting_hotkey(
+    storage: &AppStorage,
+    settings: Option<&AppSettings>,
+    app: &mut AppData,
+    plan: &mut ImportPlan,
+) {
+    if let Err(error) = validate_project_hotkey(storage, settings, app) {
+        plan.warnings
+            .push(format!("项目“{}”的快捷键未导入: {}", app.name, error));
+        app.shortcut_hotkey = None;
+    }
+}
+
+/// 合并项目
+fn merge_apps(
+    storage: &mut AppStorage,
+    imported: &[AppData],
+    id_map: &HashMap<String, String>,
+    settings: Option<&AppSettings>,
+    policy: ConflictPolicy,
+    plan: &mut ImportPlan,
+) {
+    for imported_app in imported {
+        let mut incoming = imported_app.clone();
+        translate_app_categories(&mut incoming, id_map, &storage.categories);
+
+        let matched = storage
+            .apps
+            .iter()
+            .position(|app| app.id == incoming.id)
+            .map(|index| (index, "id"))
+            .or_else(|| {
+                let path = normalize_path(&incoming.path);
+                storage
+                    .apps
+                    .iter()
+                    .position(|app| !path.is_empty() && normalize_path(&app.path) == path)
+                    .map(|index| (index, "path"))
+            });
+
+        let Some((index, matched_by)) = matched else {
+            incoming.usage_count = Some(incoming.usage_count.unwrap_or(0));
+            append_app_orders(storage, &mut incoming);
+            clear_conflicting_hotkey(storage, settings, &mut incoming, plan);
+            storage.apps.push(incoming.clone());
+            plan.added_apps.push(incoming);
+            continue;
+        };
+
+        let local = &storage.apps[index];
+        let changed_fields = changed_fields(local, &incoming, IGNORED_APP_FIELDS);
+        if changed_fields.is_empty() {
+            plan.unchanged_app_count += 1;
+            continue;
+        }
+
+        plan.conflicts.push(ImportConflict {
+            kind: "app".to_string(),
+            local_id: local.id.to_string(),
+            imported_id: incoming.id.to_string(),
+            name: local.name.clone(),
+            matched_by: matched_by.to_string(),
+            changed_fields,
+            resolution: resolution_for(policy),
+        });
+
+        match policy {
+            ConflictPolicy::KeepMine => {}
+            ConflictPolicy::TakeTheirs => {
+                // 保留本地的 id、使用统计和排序
+                let local = &storage.apps[index];
+                incoming.id = local.id;
+                incoming.copy_usage_from(local);
+                incoming.order = local.order;
+                incoming.category_orders = local.category_orders.clone();
+                normalize_app_categories(&mut incoming);
+                clear_conflicting_hotkey(storage, settings, &mut incoming, plan);
+                storage.apps[index] = incoming.clone();
+                plan.updated_apps.push(incoming);
+            }
+            ConflictPolicy::KeepBoth => {
+                incoming.id = next_app_id(storage);
+                incoming.usage_count = Some(0);
+                incoming.last_launched_at = None;
+                append_app_orders(storage, &mut incoming);
+                clear_conflicting_hotkey(storage, settings, &mut incoming, plan);
+                storage.apps.push(incoming.clone());
+                plan.added_apps.push(incoming);
+            }
+        }
+    }
+}
+
+/// 把导入的数据合并进本地数据，返回合并计划
+pub fn merge_storage(
+    storage: &mut AppStorage,
+    imported: &AppStorage,
+    settings: Option<&AppSettings>,
+    policy: ConflictPolicy,
+) -> ImportPlan {
+    let mut plan = ImportPlan::default();
+    let id_map = merge_categories(storage, &imported.categories, policy, &mut plan);
+    merge_apps(
+        storage,
+        &imported.apps,
+        &id_map,
+        settings,
+        policy,
+        &mut plan,
+    );
+    plan
+}
+
+/// 读取导入文件中的应用数据，并做与保存和整体导入相同的校验
+fn read_import_storage(file_path: &str) -> Result<AppStorage, String> {
+    let storage = read_import_file(file_path)?
+        .storage
+        .ok_or_else(|| "导入文件中没有应用数据".to_string())?;
+    validate_category_ids(&storage.categories)?;
+    validate_apps_launch_args(&storage.apps)?;
+    Ok(storage)
+}
+
+/// 预览合并导入的结果，不写入任何数据
+#[tauri::command]
+pub fn preview_import_merge(
+    state: State<'_, AppState>,
+    file_path: String,
+    policy: ConflictPolicy,
+) -> Result<ImportPlan, String> {
+    let imported = read_import_storage(&file_path)?;
+    let settings = state.store.settings().ok();
+    let mut storage = state.store.storage()?;
+    Ok(merge_storage(
+        &mut storage,
+        &imported,
+        settings.as_ref(),
+        policy,
+    ))
+}
+
+/// 把导出文件合并导入到现有数据中
+#[tauri::command]
+pub fn import_app_data_merge(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    file_path: String,
+    policy: ConflictPolicy,
+) -> Result<ImportPlan, String> {
+    let imported = read_import_storage(&file_path)?;
+    let settings = state.store.settings().ok();
+    let (plan, entry_id) = state
+        .store
+        .update_library_revertible("合并导入", |storage| {
+            Ok(merge_storage(storage, &imported, settings.as_ref(), policy))
+        })?;
+
+    if let Err(error) = crate::system_integration::refresh_global_shortcuts(&app) {
+        return Err(rollback_shortcut_registration(
+            &app,
+            &state.store,
+            entry_id,
+            error,
+        ));
+    }
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::STORAGE_SCHEMA_VERSION;
+    use std::collections::BTreeMap;
+
+    fn category(id: &str, name: &str, order: i32) -> CategoryData {
+        CategoryData {
+            id: id.to_string(),
+            name: name.to_string(),
+            icon: "icon-apps".to_string(),
+            is_default: id == "all",
+            order: Some(order),
+            hidden: false,
+        }
+    }
+
+    fn app(id: i64, name: &str, path: &str, category_ids: &[&str]) -> AppData {
+        let mut app = AppData {
+            id,
+            name: name.to_string(),
+            category: String::new(),
+            category_ids: category_ids.iter().map(|id| id.to_string()).collect(),
+            icon: String::new(),
+            path: path.to_string(),
+            target_path: None,
+            is_shortcut: false,
+            launch_args: None,
+            shortcut_hotkey: None,
+            target_type: None,
+            run_as_admin: false,
+            order: Some(0),
+            category_orders: BTreeMap::new(),
+            usage_count: Some(0),
+            last_launched_at: None,
+        };
+        normalize_app_categories(&mut app);
+        app
+    }
+
+    fn storage(apps: Vec<AppData>, categories: Vec<CategoryData>) -> AppStorage {
+        AppStorage {
+            schema_version: STORAGE_SCHEMA_VERSION,
+            apps,
+            categories,
+            selected_category: None,
+            trash: Vec::new(),
+        }
+    }
+
+    /// 本地数据：分组 all、work，项目 1 属于 work，项目 2 属于 all
+    fn local_storage() -> AppStorage {
+        let mut editor = app(1, "Editor", "/opt/editor", &["work"]);
+        editor.usage_count = Some(7);
+        editor.last_launched_at = Some(1_700_000_000);
+        editor.category_orders.insert("work".to_string(), 0);
+        let mut browser = app(2, "Browser", "/opt/browser", &["all"]);
+        browser.order = Some(1);
+        storage(
+            vec![editor, browser],
+            vec![category("all", "全部应用", 0), category("work", "Work", 1)],
+        )
+    }
+
+    fn merge(imported: &AppStorage, policy: ConflictPolicy) -> (AppStorage, ImportPlan) {
+        let mut storage = local_storage();
+        let plan = merge_storage(&mut storage, imported, None, policy);
+        (storage, plan)
+    }
+
+    fn find_app(storage: &AppStorage, id: i64) -> &AppData {
+        storage.apps.iter().find(|app| app.id == id).unwrap()
+    }
+
+    #[test]
+    fn identical_apps_and_usage_changes_are_unchanged() {
+        let mut imported = local_storage();
+        imported.apps[0].usage_count = Some(1);
+        imported.apps[0].last_launched_at = None;
+
+        let (storage, plan) = merge(&imported, ConflictPolicy::TakeTheirs);
+        assert_eq!(storage, local_storage());
+        assert_eq!(plan.unchanged_app_count, 2);
+        assert_eq!(plan.unchanged_category_count, 2);
+        assert!(plan.conflicts.is_empty());
+    }
+
+    #[test]
+    fn keep_mine_ignores_conflicting_app() {
+        let imported = storage(
+            vec![app(1, "Editor Pro", "/opt/editor", &["work"])],
+            Vec::new(),
+        );
+
+        let (storage, plan) = merge(&imported, ConflictPolicy::KeepMine);
+        assert_eq!(storage, local_storage());
+        let [conflict] = &plan.conflicts[..] else {
+            panic!("unexpected conflicts: {:?}", plan.conflicts);
+        };
+        assert_eq!(conflict.kind, "app");
+        assert_eq!(conflict.matched_by, "id");
+        assert_eq!(conflict.changed_fields, ["name"]);
+        assert_eq!(conflict.resolution, ConflictResolution::KeptMine);
+    }
+
+    #[test]
+    fn take_theirs_matches_by_path_and_keeps_local_state() {
+        let imported = storage(
+            vec![app(42, "Editor Pro", "/opt/editor/", &["work"])],
+            Vec::new(),
+        );
+
+        let (storage, plan) = merge(&imported, ConflictPolicy::TakeTheirs);
+        assert_eq!(plan.conflicts[0].matched_by, "path");
+        assert_eq!(plan.conflicts[0].resolution, ConflictResolution::TookTheirs);
+        assert_eq!(storage.apps.len(), 2);
+        let editor = find_app(&storage, 1);
+        assert_eq!(editor.name, "Editor Pro");
+        assert_eq!(editor.usage_count, Some(7));
+        assert_eq!(editor.last_launched_at, Some(1_700_000_000));
+        assert_eq!(editor.category_orders.get("work"), Some(&0));
+        assert_eq!(plan.updated_apps.len(), 1);
+    }
+
+    #[test]
+    fn keep_both_adds_imported_copy_at_the_end() {
+        let imported = storage(
+            vec![app(1, "Editor Pro", "/opt/editor", &["work"])],
+            Vec::new(),
+        );
+
+        let (storage, plan) = merge(&imported, ConflictPolicy::KeepBoth);
+        assert_eq!(plan.conflicts[0].resolution, ConflictResolution::KeptBoth);
+        assert_eq!(storage.apps.len(), 3);
+        assert_eq!(find_app(&storage, 1).name, "Editor");
+        let copy = storage.apps.last().unwrap();
+        assert_ne!(copy.id, 1);
+        assert!(copy.id > 2);
+        assert_eq!(copy.name, "Editor Pro");
+        assert_eq!(copy.usage_count, Some(0));
+        assert_eq!(copy.order, Some(2));
+        assert_eq!(copy.category_orders.get("work"), Some(&1));
+    }
+
+    #[test]
+    fn new_apps_are_appended() {
+        let imported = storage(
+            vec![app(3, "Terminal", "/opt/terminal", &["work"])],
+            Vec::new(),
+        );
+
+        let (storage, plan) = merge(&imported, ConflictPolicy::KeepMine);
+        assert_eq!(plan.added_apps.len(), 1);
+        let terminal = find_app(&storage, 3);
+        assert_eq!(terminal.order, Some(2));
+        assert_eq!(terminal.category_orders.get("work"), Some(&1));
+    }
+
+    #[test]
+    fn categories_match_by_name_and_apps_are_remapped() {
+        let imported = storage(
+            vec![
+                app(3, "Terminal", "/opt/terminal", &["c1", "c2"]),
+                app(4, "Player", "/opt/player", &["missing"]),
+            ],
+            vec![category("c1", " work ", 1), category("c2", "Games", 2)],
+        );
+
+        let (storage, plan) = merge(&imported, ConflictPolicy::KeepMine);
+        // " work " 按名称匹配到本地的 work，Games 作为新分组添加
+        assert_eq!(plan.unchanged_category_count, 0);
+        let [conflict] = &plan.conflicts[..] else {
+            panic!("unexpected conflicts: {:?}", plan.conflicts);
+        };
+        assert_eq!(conflict.matched_by, "name");
+        assert_eq!(plan.added_categories.len(), 1);
+        assert_eq!(plan.added_categories[0].order, Some(2));
+
+        assert_eq!(find_app(&storage, 3).category_ids, ["work", "c2"]);
+        // 本地和导入数据中都不存在的分组被去掉，项目归入"全部应用"
+        assert_eq!(find_app(&storage, 4).category_ids, ["all"]);
+    }
+
+    #[test]
+    fn take_theirs_updates_category_in_place() {
+        let mut renamed = category("work", "Office", 5);
+        renamed.icon = "icon-briefcase".to_string();
+        let imported = storage(Vec::new(), vec![renamed]);
+
+        let (storage, plan) = merge(&imported, ConflictPolicy::TakeTheirs);
+        let work = &storage.categories[1];
+        assert_eq!(work.name, "Office");
+        assert_eq!(work.icon, "icon-briefcase");
+        assert_eq!(work.order, Some(1));
+        assert_eq!(plan.conflicts[0].changed_fields, ["icon", "name"]);
+        assert_eq!(plan.updated_categories.len(), 1);
+    }
+
+    #[test]
+    fn keep_both_adds_category_copy_and_remaps_apps() {
+        let imported = storage(
+            vec![app(3, "Terminal", "/opt/terminal", &["work"])],
+            vec![category("work", "Office", 1)],
+        );
+
+        let (storage, plan) = merge(&imported, ConflictPolicy::KeepBoth);
+        assert_eq!(storage.categories.len(), 3);
+        let copy = storage.categories.last().unwrap();
+        assert_ne!(copy.id, "work");
+        assert_eq!(copy.name, "Office");
+        assert_eq!(plan.added_categories.len(), 1);
+        assert_eq!(&plan.added_categories[0], copy);
+        assert_eq!(find_app(&storage, 3).category_ids, [copy.id.as_str()]);
+        assert_eq!(find_app(&storage, 1).category_ids, ["work"]);
+    }
+
+    #[test]
+    fn default_category_never_conflicts() {
+        let imported = storage(Vec::new(), vec![category("all", "All Apps", 0)]);
+
+        let (storage, plan) = merge(&imported, ConflictPolicy::TakeTheirs);
+        assert_eq!(storage.categories[0].name, "全部应用");
+        assert!(plan.conflicts.is_empty());
+        assert_eq!(plan.unchanged_category_count, 1);
+    }
+}
@@ -0,0 +1,120 @@
+//! 列表差异模块
+//!
+//! 按 id 比较两个项目、分组或回收站条目列表，得到新增、删除和修改的条目，
+//! 以及顺序是否发生变化。变更广播、撤销历史、合并导入和备份比较都使用
+//! 这里的实现，需要逐字段比较时通过序列化后的字段名列出不同的字段。
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::models::{AppData, CategoryData, TrashEntry};
+
+/// 项目中随启动自动更新的使用统计字段，不属于用户的编辑
+pub const APP_USAGE_FIELDS: &[&str] = &["usage_count", "last_launched_at"];
+
+/// 可以按 id 匹配的数据项
+pub trait Keyed {
+    type Key: Eq + Hash + Clone;
+    fn key(&self) -> Self::Key;
+
+    /// 从 `current` 复制使用统计字段，用于替换数据项时保留本地的统计
+    fn copy_usage_from(&mut self, _current: &Self) {}
+}
+
+impl Keyed for AppData {
+    type Key = i64;
+    fn key(&self) -> i64 {
+        self.id
+    }
+
+    fn copy_usage_from(&mut self, current: &Self) {
+        self.usage_count = current.usage_count;
+        self.last_launched_at = current.last_launched_at;
+    }
+}
+
+impl Keyed for CategoryData {
+    type Key = String;
+    fn key(&self) -> String {
+        self.id.clone()
+    }
+}
+
+impl Keyed for TrashEntry {
+    type Key = String;
+    fn key(&self) -> String {
+        self.id.clone()
+    }
+}
+
+/// 两个列表之间的差异
+#[derive(Debug)]
+pub struct ItemDiff<'a, T> {
+    /// 只在新列表中存在的条目，按新列表的顺序
+    pub added: Vec<&'a T>,
+    /// 两侧都存在但内容不同的条目，依次为修改前和修改后的内容
+    pub modified: Vec<(&'a T, &'a T)>,
+    /// 只在旧列表中存在的条目，按旧列表的顺序
+    pub removed: Vec<&'a T>,
+    /// 两侧 id 的顺序是否不同
+    pub reordered: bool,
+}
+
+/// 按 id 比较两个列表
+pub fn diff_items<'a, T: Keyed + PartialEq>(from: &'a [T], to: &'a [T]) -> ItemDiff<'a, T> {
+    let from_by_key: HashMap<T::Key, &T> = from.iter().map(|item| (item.key(), item)).collect();
+    let to_keys: HashSet<T::Key> = to.iter().map(Keyed::key).collect();
+
+    let mut added = Vec::new();
+    let mut modified = Vec::new();
+    for item in to {
+        match from_by_key.get(&item.key()) {
+            None => added.push(item),
+            Some(previous) if *previous != item => modified.push((*previous, item)),
+            Some(_) => {}
+        }
+    }
+    let removed = from
+        .iter()
+        .filter(|item| !to_keys.contains(&item.key()))
+        .collect();
+    let reordered = !from.iter().map(Keyed::key).eq(to.iter().map(Keyed::key));
+
+    ItemDiff {
+        added,
+        modified,
+        removed,
+        reordered,
+    }
+}
+
+/// 把值序列化为 JSON 对象，无法序列化为对象时返回空对象
+pub fn to_object<T: Serialize>(value: &T) -> Map<String, Value> {
+    match serde_json::to_value(value) {
+        Ok(Value::Object(object)) => object,
+        _ => Map::new(),
+    }
+}
+
+/// 列出两个 JSON 对象中值不同的键，按字母顺序排列
+pub fn changed_keys(
+    from: &Map<String, Value>,
+    to: &Map<String, Value>,
+    ignored: &[&str],
+) -> Vec<String> {
+    let keys: HashSet<&String> = from.keys().chain(to.keys()).collect();
+    let mut fields: Vec<String> = keys
+        .into_iter()
+        .filter(|key| !ignored.contains(&key.as_str()) && from.get(*key) != to.get(*key))
+        .cloned()
+        .collect();
+    fields.sort();
+    fields
+}
+
+/// 列出两个值序列化后不同的字段，`ignored` 中的字段不参与比较
+pub fn changed_fields<T: Serialize>(from: &T, to: &T, ignored: &[&str]) -> Vec<String> {
+    changed_keys(&to_object(from), &to_object(to), ignored)
+}
@@ -1,20 +1,41 @@
 use std::sync::{Arc, Mutex};
-use tauri::{Emitter, Manager, WindowEvent};
+use tauri::{Manager, WindowEvent};
 
 mod app_launcher;
 mod backup;
+mod backup_archive;
+mod backup_inspect;
+mod backup_log;
+mod backup_schedule;
+mod backup_verify;
+mod category_manager;
+mod command_line;
 mod data;
 mod data_manager;
+mod data_scope;
+#[cfg(target_os = "linux")]
+mod desktop_entry;
+mod events;
 mod helpers;
+mod history;
+mod import_merge;
+mod item_diff;
+#[cfg(target_os = "linux")]
+mod linux_native;
+mod migration;
 mod models;
 mod settings_manager;
+mod store;
 mod system;
 mod system_integration;
+#[cfg(test)]
+mod test_support;
+mod trash;
 mod win_native;
 mod window_manager;
-mod windows;
 use crate::backup::BackupManager;
 use crate::models::AppState;
+use crate::store::Store;
 use crate::system_integration::{
     create_global_shortcut_handler, initialize_global_shortcuts, initialize_mouse_invocation,
     initialize_tray,
@@ -43,26 +64,6 @@ async fn quit_app(app: tauri::AppHandle) -> Result<String, String> {
     Ok("应用已退出".to_string())
 }
 
-// 通知主窗口刷新数据
-#[tauri::command]
-async fn notify_main_window_refresh(app: tauri::AppHandle) -> Result<String, String> {
-    crate::system_integration::refresh_global_shortcuts(&app)?;
-    if let Some(main_window) = app.get_webview_window("main") {
-        let _ = main_window.emit("data-updated", {});
-    }
-    Ok("通知已发送".to_string())
-}
-
-// 通知主窗口设置已更新
-#[tauri::command]
-async fn notify_main_settings_updated(app: tauri::AppHandle) -> Result<String, String> {
-    crate::system_integration::refresh_global_shortcuts(&app)?;
-    if let Some(main_window) = app.get_webview_window("main") {
-        let _ = main_window.emit("settings-updated", {});
-    }
-    Ok("设置更新通知已发送".to_string())
-}
-
 /// 主库文件
 ///
 /// 此文件是 Tauri 应用程序的主入口点，负责：
@@ -82,10 +83,12 @@ async fn notify_main_settings_updated(app: tauri::AppHandle) -> Result<String, S
 pub fn run() {
     // 初始化应用状态
     let app_state = AppState {
+        store: Arc::new(Store::load()),
         settings_window_open: Arc::new(Mutex::new(false)),
         new_project_window_open: Arc::new(Mutex::new(false)),
         edit_project_window_open: Arc::new(Mutex::new(false)),
         backup_handle: Arc::new(Mutex::new(None)),
+        backup_next_run: Arc::new(Mutex::new(None)),
     };
 
     tauri::Builder::default()
@@ -97,6 +100,9 @@ pub fn run() {
                 .build(),
         )
         .setup(|app| {
+            // 数据修改后向所有窗口广播变更事件
+            app.state::<AppState>().store.attach(app.handle().clone());
+
             // 初始化系统托盘
             initialize_tray(&app.handle())?;
 
@@ -111,7 +117,7 @@ pub fn run() {
                 let _ = main_window.set_skip_taskbar(true);
 
                 // 尝试读取保存的窗口大小并应用（修复重启后大小不恢复的问题）
-                if let Ok(settings) = crate::data::load_app_settings() {
+                if let Ok(settings) = app.state::<AppState>().store.settings() {
                     if let (Some(w), Some(h)) = (settings.window_width, settings.window_height) {
                         // 使用逻辑像素设置窗口大小
                         use tauri::LogicalSize;
@@ -130,7 +136,10 @@ pub fn run() {
                         )));
                     }
 
-                    if settings.start_minimized.unwrap_or(false) {
+                    // 开机自启动时通过 --minimized 参数要求启动最小化
+                    let minimized_arg =
+                        std::env::args().any(|arg| arg == system_integration::START_MINIMIZED_ARG);
+                    if settings.start_minimized.unwrap_or(false) || minimized_arg {
                         let _ = main_window.hide();
                         let _ = main_window.set_skip_taskbar(false);
                     }
@@ -146,15 +155,14 @@ pub fn run() {
                 });
             }
 
-            // 启动定时备份任务
+            // 启动定时备份任务和修改后自动备份任务
+            BackupManager::restart_scheduler(app.handle());
             let app_handle_for_backup = app.handle().clone();
-            std::thread::spawn(move || {
-                let rt = tokio::runtime::Runtime::new().unwrap();
-                rt.block_on(async {
-                    if let Err(e) = BackupManager::start_backup_task(app_handle_for_backup).await {
-                        eprintln!("启动备份任务失败: {}", e);
-                    }
-                });
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = BackupManager::start_change_backup_task(app_handle_for_backup).await
+                {
+                    eprintln!("启动备份任务失败: {}", e);
+                }
             });
 
             Ok(())
@@ -199,14 +207,22 @@ pub fn run() {
             data::update_app_category,
             data::save_selected_category,
             data::update_category_hidden,
+            category_manager::create_category,
+            category_manager::rename_category,
+            category_manager::update_category_icon,
+            category_manager::reorder_categories,
+            category_manager::delete_category,
+            category_manager::reorder_category_apps,
             app_launcher::get_app_icon,
-            windows::run_as_admin,
-            windows::open_file_location,
+            system::run_as_admin,
+            system::open_file_location,
             get_app_version,
             get_app_update_date,
             settings_manager::reset_settings_to_default,
             data::export_app_data_to_file,
             data::import_app_data_from_file,
+            import_merge::preview_import_merge,
+            import_merge::import_app_data_merge,
             data::clear_all_data,
             data::save_apps_order,
             settings_manager::update_theme,
@@ -229,16 +245,31 @@ pub fn run() {
             settings_manager::update_sort_order,
             settings_manager::update_auto_backup,
             settings_manager::update_backup_interval,
+            settings_manager::update_backup_retention,
+            settings_manager::update_backup_format,
+            settings_manager::update_change_backup,
+            settings_manager::update_trash_retention_days,
+            trash::list_trash,
+            trash::restore_trash_item,
+            trash::purge_trash,
+            history::undo_library_change,
+            history::redo_library_change,
+            history::get_history_status,
             backup::manual_backup,
             backup::get_backup_status,
+            backup::restore_backup,
+            backup::update_backup_dir,
+            backup_verify::verify_backups,
+            backup_log::get_backup_log,
+            backup_log::clear_backup_log,
+            backup_inspect::inspect_backup,
+            backup_inspect::diff_backup,
             data::save_ui_state,
             data::update_settings_batch,
-            windows::check_auto_start_status,
+            system_integration::check_auto_start_status,
             data_manager::export_data,
             data_manager::import_data,
             data_manager::reset_data,
-            notify_main_window_refresh,
-            notify_main_settings_updated
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
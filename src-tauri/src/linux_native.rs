@@ -0,0 +1,342 @@
+//! Linux 平台支持
+//!
+//! 与 `win_native` 对应，负责在 Linux 上构建并启动外部命令：
+//! - 启动可执行文件，其它文件、网址和文件夹交给 `xdg-open` 用默认程序打开
+//! - 通过文件管理器的 D-Bus 接口在文件夹中显示文件，不可用时打开所在目录
+//! - 通过 `pkexec` 以管理员权限启动
+//! - 在终端模拟器中运行需要终端的程序
+//!
+//! 命令的构建和启动分开，构建出的 `Command` 可以直接检查程序和参数。
+
+use std::ffi::OsStr;
+use std::io;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// 支持附加启动参数的常见文件管理器
+const FILE_MANAGERS: [&str; 6] = ["nautilus", "dolphin", "nemo", "caja", "thunar", "pcmanfm"];
+
+/// 常见终端模拟器及其后面跟要执行的命令的参数
+const TERMINALS: [(&str, &str); 6] = [
+    ("x-terminal-emulator", "-e"),
+    ("gnome-terminal", "--"),
+    ("konsole", "-e"),
+    ("xfce4-terminal", "-x"),
+    ("alacritty", "-e"),
+    ("xterm", "-e"),
+];
+
+/// pkexec 会清空环境变量，图形程序需要这些变量才能连接到当前会话
+const GRAPHICAL_SESSION_VARS: [&str; 5] = [
+    "DISPLAY",
+    "XAUTHORITY",
+    "WAYLAND_DISPLAY",
+    "XDG_RUNTIME_DIR",
+    "DBUS_SESSION_BUS_ADDRESS",
+];
+
+/// 判断文件是否可以直接执行
+pub fn is_executable(path: &Path) -> bool {
+    path.metadata()
+        .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+/// 在 PATH 中查找可执行程序
+pub fn find_program(name: &str) -> Option<PathBuf> {
+    std::env::var_os("PATH").and_then(|paths| {
+        std::env::split_paths(&paths)
+            .map(|dir| dir.join(name))
+            .find(|candidate| is_executable(candidate))
+    })
+}
+
+/// 用默认程序打开文件、文件夹或网址
+pub fn open_command(target: impl AsRef<OsStr>) -> Command {
+    let mut command = Command::new("xdg-open");
+    command.arg(target);
+    command
+}
+
+/// 构建启动命令：可执行文件直接运行（工作目录为所在目录），其它文件用默认程序打开
+///
+/// 用默认程序打开时无法传递启动参数，参数会被忽略。
+pub fn launch_command(path: &Path, args: &[String]) -> Command {
+    if !is_executable(path) {
+        return open_command(path);
+    }
+
+    let mut command = Command::new(path);
+    command.args(args);
+    if let Some(parent) = path.parent().filter(|parent| parent.is_dir()) {
+        command.current_dir(parent);
+    }
+    command
+}
+
+/// 构建以管理员权限启动的命令
+///
+/// 通过 `pkexec env VAR=... 程序 参数` 启动，把图形会话需要的环境变量传给程序。
+pub fn elevated_command(path: &Path, args: &[String]) -> Result<Command, String> {
+    if !is_executable(path) {
+        return Err("只能以管理员权限启动可执行文件".to_string());
+    }
+    let pkexec = find_program("pkexec").ok_or("未找到 pkexec，无法以管理员权限启动")?;
+
+    let mut command = Command::new(pkexec);
+    command.arg("env");
+    for name in GRAPHICAL_SESSION_VARS {
+        if let Some(value) = std::env::var_os(name) {
+            let mut assignment = OsStr::new(name).to_os_string();
+            assignment.push("=");
+            assignment.push(value);
+            command.arg(assignment);
+        }
+    }
+    command.arg(path).args(args);
+    Ok(command)
+}
+
+/// 构建在终端模拟器中运行程序的命令
+pub fn terminal_command(program: &Path, args: &[String]) -> Result<Command, String> {
+    let (terminal, exec_flag) = TERMINALS
+        .iter()
+        .find_map(|(name, exec_flag)| find_program(name).map(|terminal| (terminal, *exec_flag)))
+        .ok_or("未找到可用的终端模拟器")?;
+
+    let mut command = Command::new(terminal);
+    command.arg(exec_flag).arg(program).args(args);
+    Ok(command)
+}
+
+/// 构建带启动参数打开文件夹的命令
+///
+/// `xdg-open` 不接受额外参数，有参数时改用找到的第一个常见文件管理器。
+pub fn open_folder_command(folder: &Path, args: &[String]) -> Command {
+    if args.is_empty() {
+        return open_command(folder);
+    }
+    match FILE_MANAGERS.iter().find_map(|name| find_program(name)) {
+        Some(manager) => {
+            let mut command = Command::new(manager);
+            command.arg(folder).args(args);
+            command
+        }
+        None => open_command(folder),
+    }
+}
+
+/// 构建在文件管理器中显示并选中文件的命令（freedesktop FileManager1 接口）
+pub fn reveal_command(path: &Path) -> Result<Command, String> {
+    let uri = url::Url::from_file_path(path).map_err(|_| "文件路径必须是绝对路径")?;
+
+    let mut command = Command::new("dbus-send");
+    command.args([
+        "--session",
+        "--print-reply",
+        "--dest=org.freedesktop.FileManager1",
+        "--type=method_call",
+        "/org/freedesktop/FileManager1",
+        "org.freedesktop.FileManager1.ShowItems",
+    ]);
+    // dbus-send 用逗号分隔数组元素，路径中的逗号需要转义
+    command.arg(format!("array:string:{}", uri.as_str().replace(',', "%2C")));
+    command.arg("string:");
+    Ok(command)
+}
+
+/// 在后台启动命令，不等待命令结束
+///
+/// 标准输入输出重定向到空设备，防止子进程影响本进程；退出的子进程由后台线程回收。
+pub fn spawn_detached(mut command: Command) -> io::Result<()> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+/// 在文件管理器中显示文件，文件管理器不支持时打开文件所在目录
+pub fn reveal_in_file_manager(path: &Path) -> Result<(), String> {
+    let revealed = reveal_command(path).ok().is_some_and(|mut command| {
+        command
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    });
+    if revealed {
+        return Ok(());
+    }
+
+    let dir_path = path.parent().ok_or("无法获取文件目录")?;
+    spawn_detached(open_command(dir_path)).map_err(|e| format!("打开目录失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{with_env, TestDir};
+    use std::fs;
+
+    fn create_file(path: &Path, mode: u32) {
+        fs::write(path, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(path, fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    fn args_of(command: &Command) -> Vec<String> {
+        command
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn launch_command_runs_executable_in_its_directory() {
+        let dir = TestDir::new("linux_native_launch_executable");
+        let program = dir.join("app");
+        create_file(&program, 0o755);
+
+        let command = launch_command(&program, &strings(&["--name", "a b"]));
+        assert_eq!(command.get_program(), program.as_os_str());
+        assert_eq!(args_of(&command), strings(&["--name", "a b"]));
+        assert_eq!(command.get_current_dir(), Some(&*dir));
+    }
+
+    #[test]
+    fn launch_command_opens_other_files_with_default_program() {
+        let dir = TestDir::new("linux_native_launch_document");
+        let document = dir.join("notes.txt");
+        create_file(&document, 0o644);
+
+        let command = launch_command(&document, &strings(&["--ignored"]));
+        assert_eq!(command.get_program(), "xdg-open");
+        assert_eq!(args_of(&command), vec![document.display().to_string()]);
+    }
+
+    #[test]
+    fn elevated_command_forwards_session_variables() {
+        let dir = TestDir::new("linux_native_elevated");
+        let pkexec = dir.join("pkexec");
+        let program = dir.join("app");
+        create_file(&pkexec, 0o755);
+        create_file(&program, 0o755);
+
+        let command = with_env(
+            &[
+                ("PATH", Some(dir.as_os_str())),
+                ("DISPLAY", Some(OsStr::new(":1"))),
+                ("XAUTHORITY", Some(OsStr::new("/run/user/1000/xauth"))),
+                ("WAYLAND_DISPLAY", None),
+                ("XDG_RUNTIME_DIR", None),
+                ("DBUS_SESSION_BUS_ADDRESS", None),
+            ],
+            || elevated_command(&program, &strings(&["--flag"])),
+        )
+        .unwrap();
+
+        assert_eq!(command.get_program(), pkexec.as_os_str());
+        assert_eq!(
+            args_of(&command),
+            vec![
+                "env".to_string(),
+                "DISPLAY=:1".to_string(),
+                "XAUTHORITY=/run/user/1000/xauth".to_string(),
+                program.display().to_string(),
+                "--flag".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn elevated_command_requires_executable_and_pkexec() {
+        let dir = TestDir::new("linux_native_elevated_missing");
+        let program = dir.join("app");
+        let document = dir.join("notes.txt");
+        create_file(&program, 0o755);
+        create_file(&document, 0o644);
+
+        assert!(elevated_command(&document, &[]).is_err());
+        let result = with_env(&[("PATH", Some(dir.as_os_str()))], || {
+            elevated_command(&program, &[])
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn open_folder_command_uses_file_manager_only_with_args() {
+        let dir = TestDir::new("linux_native_open_folder");
+        let dolphin = dir.join("dolphin");
+        create_file(&dolphin, 0o755);
+        let folder = dir.join("folder");
+        fs::create_dir_all(&folder).unwrap();
+
+        let (plain, with_args) = with_env(&[("PATH", Some(dir.as_os_str()))], || {
+            (
+                open_folder_command(&folder, &[]),
+                open_folder_command(&folder, &strings(&["--new-window"])),
+            )
+        });
+        assert_eq!(plain.get_program(), "xdg-open");
+        assert_eq!(args_of(&plain), vec![folder.display().to_string()]);
+        assert_eq!(with_args.get_program(), dolphin.as_os_str());
+        assert_eq!(
+            args_of(&with_args),
+            vec![folder.display().to_string(), "--new-window".to_string()]
+        );
+
+        let empty = TestDir::new("linux_native_open_folder_empty");
+        let fallback = with_env(&[("PATH", Some(empty.as_os_str()))], || {
+            open_folder_command(&folder, &strings(&["--new-window"]))
+        });
+        assert_eq!(fallback.get_program(), "xdg-open");
+        assert_eq!(args_of(&fallback), vec![folder.display().to_string()]);
+    }
+
+    #[test]
+    fn reveal_command_escapes_commas_in_uri() {
+        let command = reveal_command(Path::new("/home/user/a,b/c d.txt")).unwrap();
+        assert_eq!(command.get_program(), "dbus-send");
+        let args = args_of(&command);
+        assert_eq!(
+            args[args.len() - 2..],
+            strings(&["array:string:file:///home/user/a%2Cb/c%20d.txt", "string:"])
+        );
+
+        assert!(reveal_command(Path::new("relative/file.txt")).is_err());
+    }
+
+    #[test]
+    fn terminal_command_uses_first_available_terminal() {
+        let dir = TestDir::new("linux_native_terminal");
+        let konsole = dir.join("konsole");
+        create_file(&konsole, 0o755);
+        create_file(&dir.join("xterm"), 0o755);
+        let program = Path::new("/usr/bin/htop");
+
+        let command = with_env(&[("PATH", Some(dir.as_os_str()))], || {
+            terminal_command(program, &strings(&["-d", "10"]))
+        })
+        .unwrap();
+        assert_eq!(command.get_program(), konsole.as_os_str());
+        assert_eq!(
+            args_of(&command),
+            strings(&["-e", "/usr/bin/htop", "-d", "10"])
+        );
+
+        let empty = TestDir::new("linux_native_terminal_empty");
+        let result = with_env(&[("PATH", Some(empty.as_os_str()))], || {
+            terminal_command(program, &[])
+        });
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,348 @@
+//! 数据迁移模块
+//!
+//! apps.json、settings.json、自动备份和导出文件中的数据都带有 `schema_version`，
+//! 读取时按顺序执行迁移步骤，把旧版本数据升级到当前的数据模型。
+//!
+//! 新增迁移步骤时：
+//! - 在 models 中把对应的版本号加一
+//! - 在下方迁移列表末尾追加一个步骤函数（列表长度由编译器校验）
+
+use crate::models::{SETTINGS_SCHEMA_VERSION, STORAGE_SCHEMA_VERSION};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// 单个迁移步骤，把数据从版本 N 升级到版本 N + 1
+type MigrationStep = fn(&mut Value) -> Result<(), String>;
+
+/// 没有 `schema_version` 字段的历史数据视为版本 1
+const LEGACY_SCHEMA_VERSION: u32 = 1;
+
+// 第 i 个步骤把数据从版本 i + 1 升级到 i + 2
+const STORAGE_MIGRATIONS: [MigrationStep;
+    (STORAGE_SCHEMA_VERSION - LEGACY_SCHEMA_VERSION) as usize] =
+    [storage_v1_to_v2, storage_v2_to_v3];
+
+const SETTINGS_MIGRATIONS: [MigrationStep;
+    (SETTINGS_SCHEMA_VERSION - LEGACY_SCHEMA_VERSION) as usize] = [settings_v1_to_v2];
+
+/// 把应用数据升级到当前版本，返回数据是否发生了迁移
+pub fn migrate_storage(value: &mut Value) -> Result<bool, String> {
+    run_migrations(
+        value,
+        &STORAGE_MIGRATIONS,
+        STORAGE_SCHEMA_VERSION,
+        "应用数据",
+    )
+}
+
+/// 把设置数据升级到当前版本，返回数据是否发生了迁移
+pub fn migrate_settings(value: &mut Value) -> Result<bool, String> {
+    run_migrations(value, &SETTINGS_MIGRATIONS, SETTINGS_SCHEMA_VERSION, "设置")
+}
+
+fn run_migrations(
+    value: &mut Value,
+    steps: &[MigrationStep],
+    current_version: u32,
+    label: &str,
+) -> Result<bool, String> {
+    if !value.is_object() {
+        return Err(format!("{}格式无效", label));
+    }
+
+    let version = match value.get("schema_version") {
+        None | Some(Value::Null) => LEGACY_SCHEMA_VERSION,
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| format!("{}版本号 {} 无效", label, version))?,
+    }
+    .max(LEGACY_SCHEMA_VERSION);
+
+    if version > current_version {
+        return Err(format!(
+            "{}版本 {} 高于当前支持的版本 {}，请升级 Lora",
+            label, version, current_version
+        ));
+    }
+
+    for step in &steps[(version - LEGACY_SCHEMA_VERSION) as usize..] {
+        step(value)?;
+    }
+
+    value["schema_version"] = json!(current_version);
+    Ok(version != current_version)
+}
+
+fn is_missing(object: &serde_json::Map<String, Value>, key: &str) -> bool {
+    object.get(key).is_none_or(Value::is_null)
+}
+
+// v1 -> v2：
+// - 旧版本的单一 category 合并进 category_ids，category 保持为第一个分组
+// - 缺少 order 的项目按分组内出现顺序分配排序值
+// - 缺少 usage_count 的项目补 0
+// - 缺少 order 的分组按列表位置分配排序值
+fn storage_v1_to_v2(value: &mut Value) -> Result<(), String> {
+    let mut category_order_map: HashMap<String, i64> = HashMap::new();
+
+    if let Some(apps) = value.get_mut("apps").and_then(Value::as_array_mut) {
+        for app in apps.iter_mut().filter_map(Value::as_object_mut) {
+            let mut category_ids: Vec<String> = Vec::new();
+
+            if let Some(category) = app.get("category").and_then(Value::as_str) {
+                if !category.trim().is_empty() {
+                    category_ids.push(category.to_string());
+                }
+            }
+
+            if let Some(existing_ids) = app.get("category_ids").and_then(Value::as_array) {
+                for category_id in existing_ids.iter().filter_map(Value::as_str) {
+                    if !category_id.trim().is_empty()
+                        && !category_ids.iter().any(|existing| existing == category_id)
+                    {
+                        category_ids.push(category_id.to_string());
+                    }
+                }
+            }
+
+            if category_ids.is_empty() {
+                category_ids.push("all".to_string());
+            }
+
+            if is_missing(app, "order") {
+                let next_order = category_order_map
+                    .entry(category_ids[0].clone())
+                    .or_insert(0);
+                app.insert("order".to_string(), json!(*next_order));
+                *next_order += 1;
+            }
+
+            if is_missing(app, "usage_count") {
+                app.insert("usage_count".to_string(), json!(0));
+            }
+
+            app.insert("category".to_string(), json!(category_ids[0]));
+            app.insert("category_ids".to_string(), json!(category_ids));
+        }
+    }
+
+    if let Some(categories) = value.get_mut("categories").and_then(Value::as_array_mut) {
+        for (index, category) in categories
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, category)| Some((index, category.as_object_mut()?)))
+        {
+            if is_missing(category, "order") {
+                let is_all = category.get("id").and_then(Value::as_str) == Some("all");
+                category.insert(
+                    "order".to_string(),
+                    json!(if is_all { 0 } else { index as i64 }),
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// v2 -> v3：排序从全局的 order 拆分为按分组记录的 category_orders。
+// 此前所有分组都按同一个 order 排序，因此把它复制到项目所属的每个分组，
+// 保持现有的显示顺序不变。"全部应用"仍然使用 order。
+fn storage_v2_to_v3(value: &mut Value) -> Result<(), String> {
+    if let Some(apps) = value.get_mut("apps").and_then(Value::as_array_mut) {
+        for app in apps.iter_mut().filter_map(Value::as_object_mut) {
+            if !is_missing(app, "category_orders") {
+                continue;
+            }
+
+            let mut category_orders = serde_json::Map::new();
+            if let Some(order) = app.get("order").filter(|order| order.is_i64()).cloned() {
+                let category_ids = app
+                    .get("category_ids")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                    .filter(|category_id| *category_id != "all");
+                for category_id in category_ids {
+                    category_orders.insert(category_id.to_string(), order.clone());
+                }
+            }
+            app.insert(
+                "category_orders".to_string(),
+                Value::Object(category_orders),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+// v1 -> v2：早期版本的设置文件可能缺少必填的 prevent_auto_hide
+fn settings_v1_to_v2(value: &mut Value) -> Result<(), String> {
+    if let Some(settings) = value.as_object_mut() {
+        if !settings
+            .get("prevent_auto_hide")
+            .is_some_and(Value::is_boolean)
+        {
+            settings.insert("prevent_auto_hide".to_string(), json!(false));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{parse_settings_value, parse_storage_value};
+    use std::collections::BTreeMap;
+
+    /// 读取 `tests/fixtures/migration` 中按旧版本格式保存的数据
+    fn fixture(contents: &str) -> Value {
+        serde_json::from_str(contents).unwrap()
+    }
+
+    fn storage_v1() -> Value {
+        fixture(include_str!("../tests/fixtures/migration/storage_v1.json"))
+    }
+
+    fn storage_v2() -> Value {
+        fixture(include_str!("../tests/fixtures/migration/storage_v2.json"))
+    }
+
+    fn settings_v1() -> Value {
+        fixture(include_str!("../tests/fixtures/migration/settings_v1.json"))
+    }
+
+    #[test]
+    fn storage_v1_fixture_parses_as_current_storage() {
+        let mut value = storage_v1();
+        assert!(migrate_storage(&mut value).unwrap());
+
+        let storage = parse_storage_value(value).unwrap();
+        assert_eq!(storage, parse_storage_value(storage_v1()).unwrap());
+        assert_eq!(storage.schema_version, STORAGE_SCHEMA_VERSION);
+        assert_eq!(storage.selected_category.as_deref(), Some("work"));
+        assert!(storage.trash.is_empty());
+
+        let app2 = &storage.apps[1];
+        assert_eq!(app2.category, "work");
+        assert_eq!(app2.category_ids, ["work", "tools"]);
+        assert_eq!(app2.order, Some(1));
+        assert_eq!(app2.usage_count, Some(12));
+        assert_eq!(app2.launch_args.as_deref(), Some("/k echo hello"));
+        assert_eq!(
+            app2.category_orders,
+            BTreeMap::from([("tools".to_string(), 1), ("work".to_string(), 1)])
+        );
+        let app3 = &storage.apps[2];
+        assert_eq!(app3.category_ids, ["all"]);
+        assert_eq!(app3.order, Some(0));
+        assert!(app3.category_orders.is_empty());
+
+        let orders: Vec<_> = storage
+            .categories
+            .iter()
+            .map(|category| category.order)
+            .collect();
+        assert_eq!(orders, [Some(0), Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn storage_v2_fixture_gets_per_category_orders() {
+        let mut value = storage_v2();
+        assert!(migrate_storage(&mut value).unwrap());
+        assert_eq!(value["schema_version"], json!(STORAGE_SCHEMA_VERSION));
+
+        let storage = parse_storage_value(value).unwrap();
+        assert_eq!(storage, parse_storage_value(storage_v2()).unwrap());
+
+        let app1 = &storage.apps[0];
+        assert_eq!(app1.order, Some(4));
+        assert_eq!(
+            app1.category_orders,
+            BTreeMap::from([("tools".to_string(), 4), ("work".to_string(), 4)])
+        );
+        assert_eq!(app1.shortcut_hotkey.as_deref(), Some("ctrl+alt+n"));
+        // "全部应用"仍然使用 order，没有排序值的项目不会得到分组排序
+        assert!(storage.apps[1].category_orders.is_empty());
+        assert!(storage.apps[2].category_orders.is_empty());
+        assert!(storage.categories[2].hidden);
+    }
+
+    #[test]
+    fn settings_v1_fixture_parses_as_current_settings() {
+        let mut value = settings_v1();
+        assert!(migrate_settings(&mut value).unwrap());
+
+        let settings = parse_settings_value(value).unwrap();
+        assert_eq!(settings, parse_settings_value(settings_v1()).unwrap());
+        assert_eq!(settings.schema_version, SETTINGS_SCHEMA_VERSION);
+        assert!(!settings.prevent_auto_hide);
+        assert_eq!(settings.theme.as_deref(), Some("dark"));
+        assert_eq!(settings.start_with_system, Some(true));
+        assert_eq!(settings.toggle_hotkey.as_deref(), Some("ctrl+space"));
+        assert_eq!(settings.backup_interval.as_deref(), Some("weekly"));
+    }
+
+    #[test]
+    fn migrates_v1_storage_to_current_version() {
+        let mut value = json!({
+            "apps": [
+                { "id": 1, "name": "A", "category": "work" },
+                { "id": 2, "name": "B", "category": "work", "category_ids": ["tools", "work"] },
+                { "id": 3, "name": "C", "category": "" }
+            ],
+            "categories": [
+                { "id": "all", "name": "全部" },
+                { "id": "work", "name": "工作" }
+            ]
+        });
+
+        assert!(migrate_storage(&mut value).unwrap());
+        assert_eq!(value["schema_version"], json!(STORAGE_SCHEMA_VERSION));
+
+        let apps = &value["apps"];
+        assert_eq!(apps[0]["category_ids"], json!(["work"]));
+        assert_eq!(apps[1]["category_ids"], json!(["work", "tools"]));
+        assert_eq!(apps[2]["category"], json!("all"));
+        assert_eq!(apps[0]["order"], json!(0));
+        assert_eq!(apps[1]["order"], json!(1));
+        assert_eq!(apps[0]["usage_count"], json!(0));
+        assert_eq!(apps[1]["category_orders"], json!({ "work": 1, "tools": 1 }));
+        assert_eq!(apps[2]["category_orders"], json!({}));
+        assert_eq!(value["categories"][0]["order"], json!(0));
+        assert_eq!(value["categories"][1]["order"], json!(1));
+    }
+
+    #[test]
+    fn migrates_v1_settings_to_current_version() {
+        let mut value = json!({ "theme": "dark" });
+
+        assert!(migrate_settings(&mut value).unwrap());
+        assert_eq!(value["schema_version"], json!(SETTINGS_SCHEMA_VERSION));
+        assert_eq!(value["prevent_auto_hide"], json!(false));
+        assert_eq!(value["theme"], json!("dark"));
+    }
+
+    #[test]
+    fn current_version_is_not_migrated() {
+        let mut value = json!({ "schema_version": STORAGE_SCHEMA_VERSION, "apps": [] });
+
+        assert!(!migrate_storage(&mut value).unwrap());
+    }
+
+    #[test]
+    fn rejects_future_and_invalid_versions() {
+        let mut future = json!({ "schema_version": STORAGE_SCHEMA_VERSION + 1 });
+        assert!(migrate_storage(&mut future).is_err());
+
+        let mut out_of_range = json!({ "schema_version": u64::from(u32::MAX) + 1 });
+        assert!(migrate_settings(&mut out_of_range).is_err());
+
+        let mut negative = json!({ "schema_version": -1 });
+        assert!(migrate_settings(&mut negative).is_err());
+    }
+}
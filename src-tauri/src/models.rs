@@ -1,8 +1,24 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::sync::{Arc, Mutex};
 
+use crate::store::Store;
+
+/// 应用数据（apps.json）的当前版本号，变更数据模型时需要同步添加迁移步骤
+pub const STORAGE_SCHEMA_VERSION: u32 = 3;
+/// 设置（settings.json）的当前版本号，变更数据模型时需要同步添加迁移步骤
+pub const SETTINGS_SCHEMA_VERSION: u32 = 2;
+
+fn default_storage_schema_version() -> u32 {
+    STORAGE_SCHEMA_VERSION
+}
+
+fn default_settings_schema_version() -> u32 {
+    SETTINGS_SCHEMA_VERSION
+}
+
 // 应用数据结构
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct AppData {
     pub id: i64,
     pub name: String,
@@ -19,13 +35,15 @@ pub struct AppData {
     pub target_type: Option<String>, // 目标类型: file, folder, url
     #[serde(default)]
     pub run_as_admin: bool, // 是否始终以管理员权限启动
-    pub order: Option<i32>,          // 排序字段，用于图标拖拽排序
+    pub order: Option<i32>,          // "全部应用"中的排序字段，用于图标拖拽排序
+    #[serde(default)]
+    pub category_orders: BTreeMap<String, i32>, // 项目在各分组中的排序，键为分组 id
     pub usage_count: Option<u32>,    // 使用次数
     pub last_launched_at: Option<i64>, // 上次启动时间(时间戳)
 }
 
 // 分类数据结构
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct CategoryData {
     pub id: String,
     pub name: String,
@@ -36,9 +54,32 @@ pub struct CategoryData {
     pub hidden: bool, // 是否在侧栏隐藏该分组
 }
 
+// 自动备份保留策略：保留最近 N 份，另外在指定的天数、周数、月数内
+// 每天、每周、每月各保留最新的一份
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BackupRetention {
+    pub keep_last: u32,
+    pub keep_daily_days: u32,
+    pub keep_weekly_weeks: u32,
+    pub keep_monthly_months: u32,
+}
+
+impl Default for BackupRetention {
+    fn default() -> Self {
+        Self {
+            keep_last: 10,
+            keep_daily_days: 7,
+            keep_weekly_weeks: 4,
+            keep_monthly_months: 6,
+        }
+    }
+}
+
 // 应用设置结构
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct AppSettings {
+    #[serde(default = "default_settings_schema_version")]
+    pub schema_version: u32, // 数据版本号，用于迁移旧数据
     pub prevent_auto_hide: bool,
     pub window_width: Option<u32>,
     pub window_height: Option<u32>,
@@ -72,6 +113,14 @@ pub struct AppSettings {
     pub backup_interval: Option<String>,
     pub last_backup_time: Option<i64>, // 上次备份时间(时间戳)
     pub next_backup_time: Option<i64>, // 下次计划的备份时间(时间戳)
+    pub backup_retention: Option<BackupRetention>, // 自动备份保留策略
+    pub trash_retention_days: Option<u32>, // 回收站保留天数
+    pub backup_format: Option<String>, // 自动备份格式: "zip", "json"
+    pub backup_dir: Option<String>,    // 自定义备份目录，为空时使用默认目录
+    pub backup_on_change: Option<bool>, // 应用数据修改后自动备份
+    pub backup_debounce_seconds: Option<u32>, // 修改后等待多久没有新的修改才备份(秒)
+    pub backup_min_interval_minutes: Option<u32>, // 两次自动备份之间的最短间隔(分钟)
+    pub last_backup_hash: Option<String>, // 上次备份的内容校验值
     // 界面状态记录
     pub active_tab: Option<String>,
     pub last_selected_category: Option<String>,
@@ -83,20 +132,49 @@ pub struct AppSettings {
     pub show_hidden_files: Option<bool>,
 }
 
+// 回收站中的条目
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TrashEntry {
+    pub id: String,
+    pub deleted_at: i64, // 删除时间(时间戳)
+    #[serde(flatten)]
+    pub item: TrashItem,
+}
+
+// 被删除的内容，保留完整数据以便恢复
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TrashItem {
+    App {
+        app: AppData,
+    },
+    Category {
+        category: CategoryData,
+        app_ids: Vec<i64>, // 删除时属于该分组的项目
+        #[serde(default)]
+        app_orders: BTreeMap<i64, i32>, // 删除时项目在该分组中的排序，键为项目 id
+    },
+}
+
 // 应用数据存储结构
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct AppStorage {
+    #[serde(default = "default_storage_schema_version")]
+    pub schema_version: u32, // 数据版本号，用于迁移旧数据
     pub apps: Vec<AppData>,
     pub categories: Vec<CategoryData>,
     pub selected_category: Option<String>, // 记住当前选中的分组
+    #[serde(default)]
+    pub trash: Vec<TrashEntry>, // 回收站
 }
 
 // 应用状态结构
 #[derive(Debug)]
 pub struct AppState {
+    pub store: Arc<Store>,
     pub settings_window_open: Arc<Mutex<bool>>,
     pub new_project_window_open: Arc<Mutex<bool>>,
     pub edit_project_window_open: Arc<Mutex<bool>>,
-    #[allow(dead_code)]
-    pub backup_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    pub backup_handle: Arc<Mutex<Option<tauri::async_runtime::JoinHandle<()>>>>,
+    pub backup_next_run: Arc<Mutex<Option<i64>>>, // 定时备份任务计划的下次运行时间(时间戳)
 }
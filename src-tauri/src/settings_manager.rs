@@ -7,125 +7,116 @@
 //! - 搜索设置（模糊搜索、路径搜索等）
 //! - 数据管理设置（自动备份、备份间隔等）
 
-use crate::data::{load_app_settings, save_app_settings};
-use crate::models::AppSettings;
-use crate::system_integration::set_auto_start_windows;
+use tauri::{AppHandle, Manager, State};
+
+use crate::backup::BackupManager;
+use crate::backup_schedule::BackupSchedule;
+use crate::data::get_default_settings;
+use crate::models::{AppSettings, AppState, BackupRetention};
+use crate::system_integration::set_auto_start;
 
 /// 更新阻止自动隐藏设置
 #[tauri::command]
-pub fn update_prevent_auto_hide(prevent_auto_hide: bool) -> Result<String, String> {
-    let mut settings = match load_app_settings() {
-        Ok(settings) => settings,
-        Err(_) => get_default_settings(),
-    };
-
-    settings.prevent_auto_hide = prevent_auto_hide;
-    save_app_settings(settings)?;
+pub fn update_prevent_auto_hide(
+    state: State<'_, AppState>,
+    prevent_auto_hide: bool,
+) -> Result<String, String> {
+    state.store.update_settings(|settings| {
+        settings.prevent_auto_hide = prevent_auto_hide;
+        Ok(())
+    })?;
     Ok("阻止自动隐藏设置已更新".to_string())
 }
 
-/// 获取默认设置
-pub fn get_default_settings() -> AppSettings {
-    AppSettings {
-        prevent_auto_hide: false,
-        window_width: Some(800),
-        window_height: Some(600),
-        settings_window_width: Some(800),
-        settings_window_height: Some(600),
-        new_project_window_width: Some(600),
-        new_project_window_height: Some(500),
-        window_layout: Some("horizontal".to_string()),
-        layout_locked: Some(false),
-        theme: Some("auto".to_string()),
-        icon_size: Some(88),
-        project_name_position: Some("bottom".to_string()),
-        sidebar_width: Some(0),
-        enable_animations: Some(true),
-        animation_speed: Some("normal".to_string()),
-        start_with_system: Some(false),
-        start_minimized: Some(false),
-        auto_hide_after_launch: Some(false),
-        toggle_hotkey: Some("Ctrl+Space".to_string()),
-        global_hotkey: Some(true),
-        middle_mouse_toggle: Some(false),
-        fuzzy_search: Some(true),
-        search_in_path: Some(false),
-        max_search_results: Some(20),
-        auto_backup: Some(true),
-        backup_interval: Some("weekly".to_string()),
-        last_backup_time: None,
-        next_backup_time: None,
-        // 界面状态记录默认值
-        active_tab: Some("about".to_string()),
-        last_selected_category: None,
-        window_position_x: None,
-        window_position_y: None,
-        last_search_query: None,
-        grid_view_enabled: Some(false),
-        sort_order: Some("manual".to_string()),
-        show_hidden_files: Some(false),
-    }
+/// 按设置中的开机自启动和启动最小化更新系统的自启动项
+pub fn apply_auto_start(settings: &AppSettings) -> Result<(), String> {
+    set_auto_start(
+        settings.start_with_system.unwrap_or(false),
+        settings.start_minimized.unwrap_or(false),
+    )
 }
 
 /// 重置设置到默认值
 #[tauri::command]
 pub fn reset_settings_to_default(app: tauri::AppHandle) -> Result<String, String> {
-    let previous_settings = load_app_settings()?;
+    let store = &app.state::<AppState>().store;
+    let previous_settings = store.settings()?;
     let default_settings = get_default_settings();
-    save_app_settings(default_settings)?;
+
+    // 先更新系统的自启动项，失败时设置保持不变
+    apply_auto_start(&default_settings)?;
+    if let Err(error) = store.replace_settings(default_settings) {
+        let _ = apply_auto_start(&previous_settings);
+        return Err(error);
+    }
 
     if let Err(error) = crate::system_integration::refresh_global_shortcuts(&app) {
-        save_app_settings(previous_settings)?;
+        let _ = apply_auto_start(&previous_settings);
+        store.replace_settings(previous_settings)?;
         let _ = crate::system_integration::refresh_global_shortcuts(&app);
         return Err(error);
     }
+    BackupManager::restart_scheduler(&app);
 
     Ok("设置已重置为默认值".to_string())
 }
 
 /// 更新主题设置
 #[tauri::command]
-pub fn update_theme(theme: String) -> Result<String, String> {
-    let mut settings = load_app_settings()?;
-    settings.theme = Some(theme);
-    save_app_settings(settings)?;
+pub fn update_theme(state: State<'_, AppState>, theme: String) -> Result<String, String> {
+    state.store.update_settings(|settings| {
+        settings.theme = Some(theme);
+        Ok(())
+    })?;
     Ok("主题设置已更新".to_string())
 }
 
 /// 更新窗口布局设置
 #[tauri::command]
-pub fn update_window_layout(window_layout: String) -> Result<String, String> {
+pub fn update_window_layout(
+    state: State<'_, AppState>,
+    window_layout: String,
+) -> Result<String, String> {
     if window_layout != "horizontal" && window_layout != "vertical" {
         return Err("窗口布局设置无效".to_string());
     }
 
-    let mut settings = load_app_settings()?;
-    settings.window_layout = Some(window_layout);
-    save_app_settings(settings)?;
+    state.store.update_settings(|settings| {
+        settings.window_layout = Some(window_layout);
+        Ok(())
+    })?;
     Ok("窗口布局设置已更新".to_string())
 }
 
 /// 更新布局锁定设置
 #[tauri::command]
-pub fn update_layout_locked(layout_locked: bool) -> Result<String, String> {
-    let mut settings = load_app_settings()?;
-    settings.layout_locked = Some(layout_locked);
-    save_app_settings(settings)?;
+pub fn update_layout_locked(
+    state: State<'_, AppState>,
+    layout_locked: bool,
+) -> Result<String, String> {
+    state.store.update_settings(|settings| {
+        settings.layout_locked = Some(layout_locked);
+        Ok(())
+    })?;
     Ok("布局锁定设置已更新".to_string())
 }
 
 /// 更新图标大小设置
 #[tauri::command]
-pub fn update_icon_size(icon_size: u32) -> Result<String, String> {
-    let mut settings = load_app_settings()?;
-    settings.icon_size = Some(icon_size);
-    save_app_settings(settings)?;
+pub fn update_icon_size(state: State<'_, AppState>, icon_size: u32) -> Result<String, String> {
+    state.store.update_settings(|settings| {
+        settings.icon_size = Some(icon_size);
+        Ok(())
+    })?;
     Ok("图标大小设置已更新".to_string())
 }
 
 /// 更新项目名称显示位置
 #[tauri::command]
-pub fn update_project_name_position(project_name_position: String) -> Result<String, String> {
+pub fn update_project_name_position(
+    state: State<'_, AppState>,
+    project_name_position: String,
+) -> Result<String, String> {
     if !matches!(
         project_name_position.as_str(),
         "none" | "top" | "bottom" | "left" | "right"
@@ -133,54 +124,70 @@ pub fn update_project_name_position(project_name_position: String) -> Result<Str
         return Err("项目名称显示位置无效".to_string());
     }
 
-    let mut settings = load_app_settings()?;
-    settings.project_name_position = Some(project_name_position);
-    save_app_settings(settings)?;
+    state.store.update_settings(|settings| {
+        settings.project_name_position = Some(project_name_position);
+        Ok(())
+    })?;
     Ok("项目名称显示位置已更新".to_string())
 }
 
 /// 更新侧栏宽度设置
 #[tauri::command]
-pub fn update_sidebar_width(sidebar_width: u32) -> Result<String, String> {
-    let mut settings = load_app_settings()?;
-    settings.sidebar_width = Some(sidebar_width);
-    save_app_settings(settings)?;
+pub fn update_sidebar_width(
+    state: State<'_, AppState>,
+    sidebar_width: u32,
+) -> Result<String, String> {
+    state.store.update_settings(|settings| {
+        settings.sidebar_width = Some(sidebar_width);
+        Ok(())
+    })?;
     Ok("侧栏宽度设置已更新".to_string())
 }
 
 /// 更新动画设置
 #[tauri::command]
-pub fn update_animations(enable_animations: bool) -> Result<String, String> {
-    let mut settings = load_app_settings()?;
-    settings.enable_animations = Some(enable_animations);
-    save_app_settings(settings)?;
+pub fn update_animations(
+    state: State<'_, AppState>,
+    enable_animations: bool,
+) -> Result<String, String> {
+    state.store.update_settings(|settings| {
+        settings.enable_animations = Some(enable_animations);
+        Ok(())
+    })?;
     Ok("动画设置已更新".to_string())
 }
 
 /// 更新动画速度设置
 #[tauri::command]
-pub fn update_animation_speed(animation_speed: String) -> Result<String, String> {
-    let mut settings = load_app_settings()?;
-    settings.animation_speed = Some(animation_speed);
-    save_app_settings(settings)?;
+pub fn update_animation_speed(
+    state: State<'_, AppState>,
+    animation_speed: String,
+) -> Result<String, String> {
+    state.store.update_settings(|settings| {
+        settings.animation_speed = Some(animation_speed);
+        Ok(())
+    })?;
     Ok("动画速度设置已更新".to_string())
 }
 
 /// 更新开机自启动设置
+///
+/// 先设置系统的开机自启动，成功后再保存，保证设置与系统状态一致。
 #[tauri::command]
-pub fn update_start_with_system(start_with_system: bool) -> Result<String, String> {
-    let mut settings = load_app_settings()?;
-    settings.start_with_system = Some(start_with_system);
-    save_app_settings(settings)?;
-
-    // 在 Windows 上实际设置开机自启动
-    #[cfg(target_os = "windows")]
-    {
-        if start_with_system {
-            set_auto_start_windows(true)?;
-        } else {
-            set_auto_start_windows(false)?;
-        }
+pub fn update_start_with_system(
+    state: State<'_, AppState>,
+    start_with_system: bool,
+) -> Result<String, String> {
+    let previous_settings = state.store.settings()?;
+    let start_minimized = previous_settings.start_minimized.unwrap_or(false);
+    set_auto_start(start_with_system, start_minimized)?;
+
+    if let Err(error) = state.store.update_settings(|settings| {
+        settings.start_with_system = Some(start_with_system);
+        Ok(())
+    }) {
+        let _ = apply_auto_start(&previous_settings);
+        return Err(error);
     }
 
     Ok("开机自启动设置已更新".to_string())
@@ -188,19 +195,39 @@ pub fn update_start_with_system(start_with_system: bool) -> Result<String, Strin
 
 /// 更新启动最小化设置
 #[tauri::command]
-pub fn update_start_minimized(start_minimized: bool) -> Result<String, String> {
-    let mut settings = load_app_settings()?;
-    settings.start_minimized = Some(start_minimized);
-    save_app_settings(settings)?;
+pub fn update_start_minimized(
+    state: State<'_, AppState>,
+    start_minimized: bool,
+) -> Result<String, String> {
+    let previous_settings = state.store.settings()?;
+    let start_with_system = previous_settings.start_with_system.unwrap_or(false);
+
+    // 自启动项中的启动参数随启动最小化设置更新
+    if start_with_system {
+        set_auto_start(true, start_minimized)?;
+    }
+    if let Err(error) = state.store.update_settings(|settings| {
+        settings.start_minimized = Some(start_minimized);
+        Ok(())
+    }) {
+        if start_with_system {
+            let _ = apply_auto_start(&previous_settings);
+        }
+        return Err(error);
+    }
     Ok("启动最小化设置已更新".to_string())
 }
 
 /// 更新运行应用后自动隐藏设置
 #[tauri::command]
-pub fn update_auto_hide_after_launch(auto_hide_after_launch: bool) -> Result<String, String> {
-    let mut settings = load_app_settings()?;
-    settings.auto_hide_after_launch = Some(auto_hide_after_launch);
-    save_app_settings(settings)?;
+pub fn update_auto_hide_after_launch(
+    state: State<'_, AppState>,
+    auto_hide_after_launch: bool,
+) -> Result<String, String> {
+    state.store.update_settings(|settings| {
+        settings.auto_hide_after_launch = Some(auto_hide_after_launch);
+        Ok(())
+    })?;
     Ok("运行应用后自动隐藏设置已更新".to_string())
 }
 
@@ -210,14 +237,16 @@ pub async fn update_toggle_hotkey(
     app: tauri::AppHandle,
     toggle_hotkey: String,
 ) -> Result<String, String> {
-    let mut settings = load_app_settings()?;
-    let previous_settings = settings.clone();
-
-    settings.toggle_hotkey = Some(toggle_hotkey.clone());
-    save_app_settings(settings)?;
+    let store = &app.state::<AppState>().store;
+    let previous_value = store
+        .update_settings(|settings| Ok(settings.toggle_hotkey.replace(toggle_hotkey.clone())))?;
 
     if let Err(error) = crate::system_integration::refresh_global_shortcuts(&app) {
-        save_app_settings(previous_settings)?;
+        // 只恢复本次修改的字段，不覆盖其它窗口同时修改的设置
+        store.update_settings(|settings| {
+            settings.toggle_hotkey = previous_value;
+            Ok(())
+        })?;
         let _ = crate::system_integration::refresh_global_shortcuts(&app);
         return Err(error);
     }
@@ -231,13 +260,16 @@ pub async fn update_global_hotkey(
     app: tauri::AppHandle,
     global_hotkey: bool,
 ) -> Result<String, String> {
-    let mut settings = load_app_settings()?;
-    let previous_settings = settings.clone();
-    settings.global_hotkey = Some(global_hotkey);
-    save_app_settings(settings)?;
+    let store = &app.state::<AppState>().store;
+    let previous_value =
+        store.update_settings(|settings| Ok(settings.global_hotkey.replace(global_hotkey)))?;
 
     if let Err(error) = crate::system_integration::refresh_global_shortcuts(&app) {
-        save_app_settings(previous_settings)?;
+        // 只恢复本次修改的字段，不覆盖其它窗口同时修改的设置
+        store.update_settings(|settings| {
+            settings.global_hotkey = previous_value;
+            Ok(())
+        })?;
         let _ = crate::system_integration::refresh_global_shortcuts(&app);
         return Err(error);
     }
@@ -247,68 +279,220 @@ pub async fn update_global_hotkey(
 
 /// 更新鼠标中键呼出设置
 #[tauri::command]
-pub fn update_middle_mouse_toggle(middle_mouse_toggle: bool) -> Result<String, String> {
-    let mut settings = load_app_settings()?;
-    settings.middle_mouse_toggle = Some(middle_mouse_toggle);
-    save_app_settings(settings)?;
+pub fn update_middle_mouse_toggle(
+    state: State<'_, AppState>,
+    middle_mouse_toggle: bool,
+) -> Result<String, String> {
+    state.store.update_settings(|settings| {
+        settings.middle_mouse_toggle = Some(middle_mouse_toggle);
+        Ok(())
+    })?;
     crate::system_integration::set_middle_mouse_toggle_enabled(middle_mouse_toggle);
     Ok("鼠标中键呼出设置已更新".to_string())
 }
 
 /// 更新模糊搜索设置
 #[tauri::command]
-pub fn update_fuzzy_search(fuzzy_search: bool) -> Result<String, String> {
-    let mut settings = load_app_settings()?;
-    settings.fuzzy_search = Some(fuzzy_search);
-    save_app_settings(settings)?;
+pub fn update_fuzzy_search(
+    state: State<'_, AppState>,
+    fuzzy_search: bool,
+) -> Result<String, String> {
+    state.store.update_settings(|settings| {
+        settings.fuzzy_search = Some(fuzzy_search);
+        Ok(())
+    })?;
     Ok("模糊搜索设置已更新".to_string())
 }
 
 /// 更新路径搜索设置
 #[tauri::command]
-pub fn update_search_in_path(search_in_path: bool) -> Result<String, String> {
-    let mut settings = load_app_settings()?;
-    settings.search_in_path = Some(search_in_path);
-    save_app_settings(settings)?;
+pub fn update_search_in_path(
+    state: State<'_, AppState>,
+    search_in_path: bool,
+) -> Result<String, String> {
+    state.store.update_settings(|settings| {
+        settings.search_in_path = Some(search_in_path);
+        Ok(())
+    })?;
     Ok("路径搜索设置已更新".to_string())
 }
 
 /// 更新最大搜索结果设置
 #[tauri::command]
-pub fn update_max_search_results(max_search_results: u32) -> Result<String, String> {
-    let mut settings = load_app_settings()?;
-    settings.max_search_results = Some(max_search_results);
-    save_app_settings(settings)?;
+pub fn update_max_search_results(
+    state: State<'_, AppState>,
+    max_search_results: u32,
+) -> Result<String, String> {
+    state.store.update_settings(|settings| {
+        settings.max_search_results = Some(max_search_results);
+        Ok(())
+    })?;
     Ok("最大搜索结果设置已更新".to_string())
 }
 
 /// 更新排序方式设置
 #[tauri::command]
-pub fn update_sort_order(sort_order: String) -> Result<String, String> {
+pub fn update_sort_order(state: State<'_, AppState>, sort_order: String) -> Result<String, String> {
     if !matches!(sort_order.as_str(), "manual" | "name" | "frequency") {
         return Err("排序方式无效".to_string());
     }
 
-    let mut settings = load_app_settings()?;
-    settings.sort_order = Some(sort_order);
-    save_app_settings(settings)?;
+    state.store.update_settings(|settings| {
+        settings.sort_order = Some(sort_order);
+        Ok(())
+    })?;
     Ok("排序方式设置已更新".to_string())
 }
 
 /// 更新自动备份设置
 #[tauri::command]
-pub fn update_auto_backup(auto_backup: bool) -> Result<String, String> {
-    let mut settings = load_app_settings()?;
-    settings.auto_backup = Some(auto_backup);
-    save_app_settings(settings)?;
+pub fn update_auto_backup(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    auto_backup: bool,
+) -> Result<String, String> {
+    state.store.update_settings(|settings| {
+        settings.auto_backup = Some(auto_backup);
+        Ok(())
+    })?;
+    BackupManager::restart_scheduler(&app);
     Ok("自动备份设置已更新".to_string())
 }
 
 /// 更新备份间隔设置
+///
+/// 支持预设、固定间隔和类 cron 表达式，格式见 backup_schedule 模块。
+/// 下次备份时间按新的间隔从现在开始重新计算。
 #[tauri::command]
-pub fn update_backup_interval(backup_interval: String) -> Result<String, String> {
-    let mut settings = load_app_settings()?;
-    settings.backup_interval = Some(backup_interval);
-    save_app_settings(settings)?;
+pub fn update_backup_interval(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    backup_interval: String,
+) -> Result<String, String> {
+    let schedule = BackupSchedule::parse(&backup_interval)?;
+    let next_backup_time = schedule.next_after(chrono::Local::now())?;
+
+    state.store.update_settings(|settings| {
+        settings.backup_interval = Some(backup_interval.trim().to_string());
+        settings.next_backup_time = Some(next_backup_time.timestamp());
+        Ok(())
+    })?;
+    BackupManager::restart_scheduler(&app);
     Ok("备份间隔设置已更新".to_string())
 }
+
+/// 校验备份保留策略，批量更新设置时也使用同一规则
+pub fn validate_backup_retention(retention: &BackupRetention) -> Result<(), String> {
+    if retention.keep_last == 0 {
+        return Err("至少需要保留 1 份最近的备份".to_string());
+    }
+    Ok(())
+}
+
+/// 更新自动备份保留策略
+#[tauri::command]
+pub fn update_backup_retention(
+    state: State<'_, AppState>,
+    backup_retention: BackupRetention,
+) -> Result<String, String> {
+    validate_backup_retention(&backup_retention)?;
+
+    state.store.update_settings(|settings| {
+        settings.backup_retention = Some(backup_retention);
+        Ok(())
+    })?;
+    Ok("备份保留策略已更新".to_string())
+}
+
+/// 校验自动备份格式，批量更新设置时也使用同一规则
+pub fn validate_backup_format(backup_format: &str) -> Result<(), String> {
+    if !matches!(backup_format, "zip" | "json") {
+        return Err(format!("不支持的备份格式: {}", backup_format));
+    }
+    Ok(())
+}
+
+/// 更新自动备份格式，"zip" 为压缩归档，"json" 为旧版 JSON 文件
+#[tauri::command]
+pub fn update_backup_format(
+    state: State<'_, AppState>,
+    backup_format: String,
+) -> Result<String, String> {
+    validate_backup_format(&backup_format)?;
+
+    state.store.update_settings(|settings| {
+        settings.backup_format = Some(backup_format);
+        Ok(())
+    })?;
+    Ok("备份格式设置已更新".to_string())
+}
+
+/// 校验修改后自动备份的等待时间，批量更新设置时也使用同一规则
+pub fn validate_backup_debounce_seconds(debounce_seconds: u32) -> Result<(), String> {
+    if !(5..=86400).contains(&debounce_seconds) {
+        return Err("等待时间必须在 5 秒到 24 小时之间".to_string());
+    }
+    Ok(())
+}
+
+/// 校验两次自动备份之间的最短间隔，批量更新设置时也使用同一规则
+pub fn validate_backup_min_interval_minutes(min_interval_minutes: u32) -> Result<(), String> {
+    if min_interval_minutes > 10080 {
+        return Err("最短备份间隔不能超过 7 天".to_string());
+    }
+    Ok(())
+}
+
+/// 更新修改后自动备份的设置
+///
+/// `debounce_seconds` 为最后一次修改后等待的时间，`min_interval_minutes`
+/// 为两次自动备份之间的最短间隔，未指定时保持原值。
+#[tauri::command]
+pub fn update_change_backup(
+    state: State<'_, AppState>,
+    enabled: bool,
+    debounce_seconds: Option<u32>,
+    min_interval_minutes: Option<u32>,
+) -> Result<String, String> {
+    if let Some(debounce_seconds) = debounce_seconds {
+        validate_backup_debounce_seconds(debounce_seconds)?;
+    }
+    if let Some(min_interval_minutes) = min_interval_minutes {
+        validate_backup_min_interval_minutes(min_interval_minutes)?;
+    }
+
+    state.store.update_settings(|settings| {
+        settings.backup_on_change = Some(enabled);
+        if debounce_seconds.is_some() {
+            settings.backup_debounce_seconds = debounce_seconds;
+        }
+        if min_interval_minutes.is_some() {
+            settings.backup_min_interval_minutes = min_interval_minutes;
+        }
+        Ok(())
+    })?;
+    Ok("修改后自动备份设置已更新".to_string())
+}
+
+/// 校验回收站保留天数，批量更新设置时也使用同一规则
+pub fn validate_trash_retention_days(trash_retention_days: u32) -> Result<(), String> {
+    if trash_retention_days > 3650 {
+        return Err("回收站保留天数不能超过 3650 天".to_string());
+    }
+    Ok(())
+}
+
+/// 更新回收站保留天数设置
+#[tauri::command]
+pub fn update_trash_retention_days(
+    state: State<'_, AppState>,
+    trash_retention_days: u32,
+) -> Result<String, String> {
+    validate_trash_retention_days(trash_retention_days)?;
+
+    state.store.update_settings(|settings| {
+        settings.trash_retention_days = Some(trash_retention_days);
+        Ok(())
+    })?;
+    Ok("回收站保留天数设置已更新".to_string())
+}
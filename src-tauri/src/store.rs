@@ -0,0 +1,361 @@
+//! 数据存储模块
+//!
+//! 应用数据（apps.json）和设置（settings.json）在启动时从磁盘加载一次，
+//! 之后所有读取都直接返回内存中的副本。所有修改都经过 Store 在锁内完成
+//! “修改-写盘-提交”，多个窗口同时修改数据时不会互相覆盖。
+//!
+//! 每次提交后都会在释放锁之后通过 events 模块向所有窗口广播本次修改的差异。
+//! 通过 `update_library`/`replace_library` 提交的修改会记录到撤销历史中，
+//! 并通知变更触发的自动备份任务；使用统计等通过 `update_storage` 提交的
+//! 修改不会触发自动备份。
+
+use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+use tauri::AppHandle;
+use tokio::sync::Notify;
+
+use crate::data::{
+    read_app_data_file, read_app_settings_file, write_app_data_file, write_app_settings_file,
+};
+use crate::events::{
+    diff_settings, diff_storage, emit_library_change, emit_settings_change, LibraryChange,
+    SettingsChange,
+};
+use crate::history::{History, HistoryEntry, HistoryStatus};
+use crate::models::{AppSettings, AppStorage};
+
+/// 内存中的权威数据存储，作为 `AppState` 的一部分由 Tauri 管理
+///
+/// 启动时加载失败（例如文件损坏且无法恢复）会记录错误，之后的读取和
+/// 增量修改都会返回该错误，避免用空数据覆盖磁盘上的文件；整体替换
+/// （导入、重置）可以清除该错误。
+#[derive(Debug)]
+pub struct Store {
+    storage: Mutex<Result<AppStorage, String>>,
+    settings: Mutex<Result<AppSettings, String>>,
+    // 只在持有 storage 锁时访问，保证历史记录与数据一致
+    history: Mutex<History>,
+    app_handle: OnceLock<AppHandle>,
+    library_changed: Notify,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // 只有写盘成功后才会提交修改，锁中毒时内存数据仍然完整
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl Store {
+    /// 从磁盘加载应用数据和设置
+    pub fn load() -> Self {
+        let storage = read_app_data_file();
+        if let Err(error) = &storage {
+            eprintln!("加载应用数据失败: {}", error);
+        }
+
+        let settings = read_app_settings_file();
+        if let Err(error) = &settings {
+            eprintln!("加载设置失败: {}", error);
+        }
+
+        Self {
+            storage: Mutex::new(storage),
+            settings: Mutex::new(settings),
+            history: Mutex::new(History::default()),
+            app_handle: OnceLock::new(),
+            library_changed: Notify::new(),
+        }
+    }
+
+    /// 关联应用句柄，之后的每次修改都会向所有窗口广播变更事件
+    pub fn attach(&self, app: AppHandle) {
+        let _ = self.app_handle.set(app);
+    }
+
+    /// 写盘并提交应用数据，返回需要广播的变更
+    ///
+    /// 变更由调用方在释放锁之后通过 `broadcast_library` 广播，避免持锁期间等待窗口。
+    fn commit_storage(
+        &self,
+        guard: &mut MutexGuard<'_, Result<AppStorage, String>>,
+        storage: AppStorage,
+    ) -> Result<Option<LibraryChange>, String> {
+        write_app_data_file(&storage)?;
+
+        let change = self
+            .app_handle
+            .get()
+            .map(|_| diff_storage(guard.as_ref().ok(), &storage));
+        **guard = Ok(storage);
+        Ok(change)
+    }
+
+    /// 提交用户对应用数据的修改，并通知变更触发的自动备份任务
+    fn commit_library(
+        &self,
+        guard: &mut MutexGuard<'_, Result<AppStorage, String>>,
+        storage: AppStorage,
+    ) -> Result<Option<LibraryChange>, String> {
+        let change = self.commit_storage(guard, storage)?;
+        self.library_changed.notify_one();
+        Ok(change)
+    }
+
+    /// 写盘并提交设置，返回需要在释放锁之后广播的变更
+    fn commit_settings(
+        &self,
+        guard: &mut MutexGuard<'_, Result<AppSettings, String>>,
+        settings: AppSettings,
+    ) -> Result<Option<SettingsChange>, String> {
+        write_app_settings_file(&settings)?;
+
+        let change = self
+            .app_handle
+            .get()
+            .map(|_| diff_settings(guard.as_ref().ok(), &settings));
+        **guard = Ok(settings);
+        Ok(change)
+    }
+
+    fn broadcast_library(&self, change: Option<LibraryChange>) {
+        if let (Some(app), Some(change)) = (self.app_handle.get(), change) {
+            emit_library_change(app, &change);
+        }
+    }
+
+    fn broadcast_settings(&self, change: Option<SettingsChange>) {
+        if let (Some(app), Some(change)) = (self.app_handle.get(), change) {
+            emit_settings_change(app, &change);
+        }
+    }
+
+    /// 用户修改应用数据（包括撤销、重做和回滚）后发出通知，没有等待者时保留一次通知
+    pub fn library_changed(&self) -> &Notify {
+        &self.library_changed
+    }
+
+    /// 获取应用数据的副本
+    pub fn storage(&self) -> Result<AppStorage, String> {
+        lock(&self.storage).clone()
+    }
+
+    /// 获取设置的副本
+    pub fn settings(&self) -> Result<AppSettings, String> {
+        lock(&self.settings).clone()
+    }
+
+    /// 在不复制整份数据的情况下读取应用数据
+    pub fn with_storage<R>(&self, read: impl FnOnce(&AppStorage) -> R) -> Result<R, String> {
+        match &*lock(&self.storage) {
+            Ok(storage) => Ok(read(storage)),
+            Err(error) => Err(error.clone()),
+        }
+    }
+
+    /// 修改应用数据并写盘，写盘失败时内存数据保持不变
+    pub fn update_storage<R>(
+        &self,
+        update: impl FnOnce(&mut AppStorage) -> Result<R, String>,
+    ) -> Result<R, String> {
+        let mut guard = lock(&self.storage);
+        let mut storage = guard.as_ref().map_err(|error| error.clone())?.clone();
+
+        let result = update(&mut storage)?;
+        let change = self.commit_storage(&mut guard, storage)?;
+        drop(guard);
+
+        self.broadcast_library(change);
+        Ok(result)
+    }
+
+    /// 修改设置并写盘，写盘失败时内存数据保持不变
+    pub fn update_settings<R>(
+        &self,
+        update: impl FnOnce(&mut AppSettings) -> Result<R, String>,
+    ) -> Result<R, String> {
+        let mut guard = lock(&self.settings);
+        let mut settings = guard.as_ref().map_err(|error| error.clone())?.clone();
+
+        let result = update(&mut settings)?;
+        let change = self.commit_settings(&mut guard, settings)?;
+        drop(guard);
+
+        self.broadcast_settings(change);
+        Ok(result)
+    }
+
+    /// 修改应用数据并写盘，同时记录一条可撤销的历史
+    pub fn update_library<R>(
+        &self,
+        label: &str,
+        update: impl FnOnce(&mut AppStorage) -> Result<R, String>,
+    ) -> Result<R, String> {
+        self.update_library_revertible(label, update)
+            .map(|(result, _)| result)
+    }
+
+    /// 与 `update_library` 相同，同时返回本次修改的历史记录 id，数据没有变化时为 None
+    pub fn update_library_revertible<R>(
+        &self,
+        label: &str,
+        update: impl FnOnce(&mut AppStorage) -> Result<R, String>,
+    ) -> Result<(R, Option<u64>), String> {
+        let mut guard = lock(&self.storage);
+        let previous = guard.as_ref().map_err(|error| error.clone())?;
+        let mut storage = previous.clone();
+
+        let result = update(&mut storage)?;
+        let entry = HistoryEntry::between(label, previous, &storage);
+        let change = self.commit_library(&mut guard, storage)?;
+
+        let entry_id = entry.map(|entry| lock(&self.history).record(entry));
+        drop(guard);
+
+        self.broadcast_library(change);
+        Ok((result, entry_id))
+    }
+
+    /// 整体替换应用数据并写盘，同时记录一条可撤销的历史，返回历史记录 id
+    pub fn replace_library(&self, label: &str, storage: AppStorage) -> Result<Option<u64>, String> {
+        let mut guard = lock(&self.storage);
+        let was_loaded = guard.is_ok();
+        let entry = guard
+            .as_ref()
+            .ok()
+            .and_then(|previous| HistoryEntry::between(label, previous, &storage));
+        let change = self.commit_library(&mut guard, storage)?;
+
+        let mut history = lock(&self.history);
+        let entry_id = match entry {
+            Some(entry) => Some(history.record(entry)),
+            None => {
+                // 加载失败后的整体替换无法撤销，之前的记录也不再适用
+                if !was_loaded {
+                    *history = History::default();
+                }
+                None
+            }
+        };
+        drop(history);
+        drop(guard);
+
+        self.broadcast_library(change);
+        Ok(entry_id)
+    }
+
+    /// 回滚刚刚提交的一次修改，并丢弃对应的历史记录
+    ///
+    /// 在锁内把这次修改涉及的内容恢复为修改前的状态，期间其它窗口提交的修改保持不变。
+    pub fn rollback_library(&self, entry_id: u64) -> Result<(), String> {
+        let mut guard = lock(&self.storage);
+        let mut storage = guard.as_ref().map_err(|error| error.clone())?.clone();
+
+        let mut history = lock(&self.history);
+        let (entry, undone) = history.take(entry_id).ok_or("修改记录已不存在，无法回滚")?;
+        // 已经被撤销的修改不需要再次回滚
+        if undone {
+            return Ok(());
+        }
+
+        entry.apply(&mut storage, false);
+        let change = match self.commit_library(&mut guard, storage) {
+            Ok(change) => change,
+            Err(error) => {
+                history.push_undo(entry);
+                return Err(error);
+            }
+        };
+        drop(history);
+        drop(guard);
+
+        self.broadcast_library(change);
+        Ok(())
+    }
+
+    /// 撤销最近一次修改，返回被撤销操作的名称和历史记录 id，没有可撤销的操作时返回 None
+    pub fn undo_storage(&self) -> Result<Option<(String, u64)>, String> {
+        self.step_history(false)
+    }
+
+    /// 重做最近一次被撤销的修改，返回被重做操作的名称和历史记录 id
+    pub fn redo_storage(&self) -> Result<Option<(String, u64)>, String> {
+        self.step_history(true)
+    }
+
+    fn step_history(&self, forward: bool) -> Result<Option<(String, u64)>, String> {
+        let mut guard = lock(&self.storage);
+        let mut storage = guard.as_ref().map_err(|error| error.clone())?.clone();
+
+        let mut history = lock(&self.history);
+        let entry = if forward {
+            history.pop_redo()
+        } else {
+            history.pop_undo()
+        };
+        let Some(entry) = entry else {
+            return Ok(None);
+        };
+
+        entry.apply(&mut storage, forward);
+        let committed = self.commit_library(&mut guard, storage);
+        let step = (entry.label().to_string(), entry.id());
+
+        // 写盘失败时记录放回原处，成功时移到另一侧
+        match (committed.is_ok(), forward) {
+            (true, true) | (false, false) => history.push_undo(entry),
+            (true, false) | (false, true) => history.push_redo(entry),
+        }
+        drop(history);
+        drop(guard);
+
+        self.broadcast_library(committed?);
+        Ok(Some(step))
+    }
+
+    /// 恢复一次撤销（`forward` 为 false）或重做（`forward` 为 true）之前的状态
+    ///
+    /// 按 id 找到对应的记录，而不是最近的一条，期间其它窗口提交的修改不受影响。
+    /// 记录已经被其它撤销/重做移动过时不做任何修改。
+    pub fn revert_history_step(&self, entry_id: u64, forward: bool) -> Result<(), String> {
+        let mut guard = lock(&self.storage);
+        let mut storage = guard.as_ref().map_err(|error| error.clone())?.clone();
+
+        let mut history = lock(&self.history);
+        let (entry, undone) = history.take(entry_id).ok_or("修改记录已不存在，无法恢复")?;
+        // 重做之后记录位于撤销列表，撤销之后位于重做列表
+        if undone == forward {
+            if undone {
+                history.push_redo(entry);
+            } else {
+                history.push_undo(entry);
+            }
+            return Ok(());
+        }
+
+        entry.apply(&mut storage, !forward);
+        let committed = self.commit_library(&mut guard, storage);
+        match (committed.is_ok(), forward) {
+            (true, true) | (false, false) => history.push_redo(entry),
+            (true, false) | (false, true) => history.push_undo(entry),
+        }
+        drop(history);
+        drop(guard);
+
+        self.broadcast_library(committed?);
+        Ok(())
+    }
+
+    /// 获取撤销/重做状态
+    pub fn history_status(&self) -> HistoryStatus {
+        let _guard = lock(&self.storage);
+        lock(&self.history).status()
+    }
+
+    /// 整体替换设置并写盘
+    pub fn replace_settings(&self, settings: AppSettings) -> Result<(), String> {
+        let mut guard = lock(&self.settings);
+        let change = self.commit_settings(&mut guard, settings)?;
+        drop(guard);
+
+        self.broadcast_settings(change);
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use std::path::Path;
-#[cfg(not(target_os = "windows"))]
+#[cfg(target_os = "macos")]
 use std::process::Command;
 
 #[tauri::command]
@@ -42,15 +42,11 @@ pub fn open_url(url: String, launch_args: Option<String>) -> Result<String, Stri
 
     #[cfg(target_os = "macos")]
     {
-        let mut cmd = Command::new("open").creation_flags(0x08000000);
+        let mut cmd = Command::new("open");
         cmd.arg(&url);
-        if let Some(args_str) = launch_args {
-            if !args_str.trim().is_empty() {
-                let split_args: Vec<String> =
-                    args_str.split_whitespace().map(|s| s.to_string()).collect();
-                cmd.args(&split_args);
-            }
-        }
+        cmd.args(crate::command_line::split_launch_args(
+            launch_args.as_deref(),
+        )?);
         let result = cmd.spawn();
         return result
             .map(|_| "网址打开成功".to_string())
@@ -59,19 +55,13 @@ pub fn open_url(url: String, launch_args: Option<String>) -> Result<String, Stri
 
     #[cfg(target_os = "linux")]
     {
-        let mut cmd = Command::new("xdg-open").creation_flags(0x08000000);
-        cmd.arg(&url);
-        if let Some(args_str) = launch_args {
-            if !args_str.trim().is_empty() {
-                let split_args: Vec<String> =
-                    args_str.split_whitespace().map(|s| s.to_string()).collect();
-                cmd.args(&split_args);
-            }
+        // xdg-open 只接受一个参数，无法把启动参数传给默认浏览器
+        if !crate::command_line::split_launch_args(launch_args.as_deref())?.is_empty() {
+            return Err("Linux 上打开网址不支持启动参数".to_string());
         }
-        let result = cmd.spawn();
-        return result
+        crate::linux_native::spawn_detached(crate::linux_native::open_command(&url))
             .map(|_| "网址打开成功".to_string())
-            .map_err(|e| format!("打开网址失败: {}", e));
+            .map_err(|e| format!("打开网址失败: {}", e))
     }
 
     #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
@@ -102,15 +92,11 @@ pub fn open_folder(folder_path: String, launch_args: Option<String>) -> Result<S
 
     #[cfg(target_os = "macos")]
     {
-        let mut cmd = Command::new("open").creation_flags(0x08000000);
+        let mut cmd = Command::new("open");
         cmd.arg(&folder_path);
-        if let Some(args_str) = launch_args {
-            if !args_str.trim().is_empty() {
-                let split_args: Vec<String> =
-                    args_str.split_whitespace().map(|s| s.to_string()).collect();
-                cmd.args(&split_args);
-            }
-        }
+        cmd.args(crate::command_line::split_launch_args(
+            launch_args.as_deref(),
+        )?);
         let result = cmd.spawn();
         return result
             .map(|_| "文件夹打开成功".to_string())
@@ -119,30 +105,10 @@ pub fn open_folder(folder_path: String, launch_args: Option<String>) -> Result<S
 
     #[cfg(target_os = "linux")]
     {
-        let file_managers = ["nautilus", "dolphin", "thunar", "pcmanfm"];
-        for manager in &file_managers {
-            if Command::new("which")
-                .creation_flags(0x08000000)
-                .arg(manager)
-                .output()
-                .is_ok()
-            {
-                let mut cmd = Command::new(manager).creation_flags(0x08000000);
-                cmd.arg(&folder_path);
-                if let Some(args_str) = launch_args {
-                    if !args_str.trim().is_empty() {
-                        let split_args: Vec<String> =
-                            args_str.split_whitespace().map(|s| s.to_string()).collect();
-                        cmd.args(&split_args);
-                    }
-                }
-                let result = cmd.spawn();
-                if result.is_ok() {
-                    return Ok("文件夹打开成功".to_string());
-                }
-            }
-        }
-        Err("未找到可用的文件管理器".to_string())
+        let args = crate::command_line::split_launch_args(launch_args.as_deref())?;
+        crate::linux_native::spawn_detached(crate::linux_native::open_folder_command(path, &args))
+            .map(|_| "文件夹打开成功".to_string())
+            .map_err(|e| format!("打开文件夹失败: {}", e))
     }
 
     #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
@@ -151,6 +117,65 @@ pub fn open_folder(folder_path: String, launch_args: Option<String>) -> Result<S
     }
 }
 
+#[tauri::command]
+pub fn run_as_admin(app_path: String, launch_args: Option<String>) -> Result<String, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let work_dir = Path::new(&app_path).parent().and_then(|p| p.to_str());
+        let params = launch_args
+            .as_deref()
+            .filter(|args| !args.trim().is_empty());
+        return crate::win_native::shell_execute(&app_path, params, work_dir, Some("runas"))
+            .map(|_| "应用以管理员权限启动成功".to_string())
+            .map_err(|e| format!("以管理员权限启动应用失败: {}", e));
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let args = crate::command_line::split_launch_args(launch_args.as_deref())?;
+        crate::linux_native::elevated_command(Path::new(&app_path), &args)
+            .and_then(|command| {
+                crate::linux_native::spawn_detached(command).map_err(|e| e.to_string())
+            })
+            .map(|_| "应用以管理员权限启动成功".to_string())
+            .map_err(|e| format!("以管理员权限启动应用失败: {}", e))
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        let _ = (app_path, launch_args);
+        Err("当前平台不支持以管理员权限启动".to_string())
+    }
+}
+
+#[tauri::command]
+pub fn open_file_location(file_path: String) -> Result<String, String> {
+    let path = Path::new(&file_path);
+
+    #[cfg(target_os = "windows")]
+    {
+        let dir_path = path
+            .parent()
+            .ok_or("无法获取文件目录")?
+            .to_str()
+            .ok_or("路径包含无效字符")?;
+        return crate::win_native::shell_execute(dir_path, None, None, Some("open"))
+            .map(|_| "已打开文件所在目录".to_string())
+            .map_err(|e| format!("打开目录失败: {}", e));
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        crate::linux_native::reveal_in_file_manager(path).map(|_| "已打开文件所在目录".to_string())
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        let _ = path;
+        Err("当前平台不支持打开文件所在目录".to_string())
+    }
+}
+
 #[tauri::command]
 pub fn open_file_dialog(
     title: String,
@@ -163,6 +188,7 @@ pub fn open_file_dialog(
 
     #[cfg(not(target_os = "windows"))]
     {
+        let _ = (title, filters);
         Err("当前平台不支持文件选择对话框".to_string())
     }
 }
@@ -176,6 +202,7 @@ pub fn open_folder_dialog(title: String) -> Result<String, String> {
 
     #[cfg(not(target_os = "windows"))]
     {
+        let _ = title;
         Err("当前平台不支持文件夹选择对话框".to_string())
     }
 }
@@ -12,8 +12,8 @@ use tauri::{
 };
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 
-use crate::data::{load_app_data, load_app_settings};
-use crate::models::{AppData, AppSettings};
+use crate::data::get_default_settings;
+use crate::models::{AppData, AppState};
 
 #[cfg(target_os = "windows")]
 mod mouse_invocation {
@@ -90,7 +90,11 @@ mod mouse_invocation {
 
 /// 初始化可选的全局鼠标中键呼出功能。
 pub fn initialize_mouse_invocation(app: &AppHandle) {
-    let settings = load_app_settings().unwrap_or_else(|_| get_default_settings());
+    let settings = app
+        .state::<AppState>()
+        .store
+        .settings()
+        .unwrap_or_else(|_| get_default_settings());
     let enabled = settings.middle_mouse_toggle.unwrap_or(false);
 
     #[cfg(target_os = "windows")]
@@ -119,6 +123,59 @@ pub fn set_auto_start_windows(enable: bool) -> Result<(), String> {
     crate::win_native::set_auto_start("Lora", exe_path_str, enable)
 }
 
+/// 开机自启动时传给应用的启动最小化参数
+pub const START_MINIMIZED_ARG: &str = "--minimized";
+
+/// 设置开机自启动
+///
+/// Windows 写入注册表启动项；Linux 写入 XDG 自启动目录中的桌面项，
+/// 启用启动最小化时在启动命令中附加 `--minimized`。
+pub fn set_auto_start(enable: bool, start_minimized: bool) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let _ = start_minimized;
+        set_auto_start_windows(enable)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        // AppImage 运行时 current_exe 指向临时挂载目录，需要使用 AppImage 文件本身
+        let exe_path = match std::env::var_os("APPIMAGE") {
+            Some(app_image) => std::path::PathBuf::from(app_image),
+            None => {
+                std::env::current_exe().map_err(|e| format!("获取可执行文件路径失败: {}", e))?
+            }
+        };
+        let minimized_arg = start_minimized.then_some(START_MINIMIZED_ARG);
+        crate::desktop_entry::set_auto_start(&exe_path, enable, minimized_arg)
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        let _ = (enable, start_minimized);
+        Ok(())
+    }
+}
+
+/// 检查开机自启动是否已启用
+#[tauri::command]
+pub fn check_auto_start_status() -> Result<bool, String> {
+    #[cfg(target_os = "windows")]
+    {
+        crate::win_native::is_auto_start_enabled("Lora")
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        crate::desktop_entry::is_auto_start_enabled()
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        Ok(false)
+    }
+}
+
 /// 更新托盘菜单项
 #[tauri::command]
 pub async fn update_tray_menu(_app: AppHandle, _prevent_auto_hide: bool) -> Result<String, String> {
@@ -225,8 +282,9 @@ pub fn initialize_global_shortcuts(app: &AppHandle) -> Result<(), Box<dyn std::e
 
 /// 重新注册窗口呼出与所有单项目快捷键。
 pub fn refresh_global_shortcuts(app: &AppHandle) -> Result<(), String> {
-    let settings = load_app_settings().unwrap_or_else(|_| get_default_settings());
-    let storage = load_app_data()?;
+    let store = &app.state::<AppState>().store;
+    let settings = store.settings().unwrap_or_else(|_| get_default_settings());
+    let storage = store.storage()?;
     let mut registrations: Vec<(Shortcut, String)> = Vec::new();
 
     if settings.global_hotkey.unwrap_or(true) {
@@ -290,9 +348,12 @@ fn shortcut_id(value: &str) -> Option<u32> {
 }
 
 fn launch_project_from_shortcut(app: &AppHandle, project: &AppData) {
-    let target_path = if project.path.to_lowercase().ends_with(".lnk")
-        || project.path.to_lowercase().ends_with(".url")
-    {
+    let path = project.path.to_lowercase();
+    let is_shortcut_file = path.ends_with(".lnk") || path.ends_with(".url");
+    // .desktop 文件中的网址链接按网址打开，应用直接启动 .desktop 文件本身
+    let is_desktop_entry =
+        path.ends_with(".desktop") && project.target_type.as_deref() != Some("url");
+    let target_path = if is_shortcut_file || is_desktop_entry {
         project.path.clone()
     } else {
         project
@@ -312,10 +373,9 @@ fn launch_project_from_shortcut(app: &AppHandle, project: &AppData) {
 
     match result {
         Ok(_) => {
-            if let Err(error) = crate::data::increment_app_usage(project.id) {
+            if let Err(error) = crate::data::increment_app_usage(app.state(), project.id) {
                 eprintln!("更新项目快捷键使用次数失败: {}", error);
             }
-            let _ = app.emit("data-updated", {});
         }
         Err(error) => eprintln!("项目快捷键启动“{}”失败: {}", project.name, error),
     }
@@ -332,16 +392,25 @@ pub fn create_global_shortcut_handler(
 
         println!("快捷键按下触发: {:?}, 事件: {:?}", shortcut, event);
 
-        let settings = load_app_settings().unwrap_or_else(|_| get_default_settings());
+        let store = &app.state::<AppState>().store;
+        let settings = store.settings().unwrap_or_else(|_| get_default_settings());
         let is_window_toggle = settings.global_hotkey.unwrap_or(true)
             && settings.toggle_hotkey.as_deref().and_then(shortcut_id) == Some(shortcut.id());
 
         if !is_window_toggle {
-            let project = load_app_data().ok().and_then(|storage| {
-                storage.apps.into_iter().find(|project| {
-                    project.shortcut_hotkey.as_deref().and_then(shortcut_id) == Some(shortcut.id())
+            let project = store
+                .with_storage(|storage| {
+                    storage
+                        .apps
+                        .iter()
+                        .find(|project| {
+                            project.shortcut_hotkey.as_deref().and_then(shortcut_id)
+                                == Some(shortcut.id())
+                        })
+                        .cloned()
                 })
-            });
+                .ok()
+                .flatten();
 
             if let Some(project) = project {
                 launch_project_from_shortcut(app, &project);
@@ -415,46 +484,3 @@ pub fn create_global_shortcut_handler(
         }
     }
 }
-
-/// 获取默认设置
-fn get_default_settings() -> AppSettings {
-    AppSettings {
-        prevent_auto_hide: false,
-        window_width: Some(800),
-        window_height: Some(600),
-        settings_window_width: Some(800),
-        settings_window_height: Some(600),
-        new_project_window_width: Some(600),
-        new_project_window_height: Some(500),
-        window_layout: Some("horizontal".to_string()),
-        layout_locked: Some(false),
-        theme: Some("auto".to_string()),
-        icon_size: Some(88),
-        project_name_position: Some("bottom".to_string()),
-        sidebar_width: Some(0),
-        enable_animations: Some(true),
-        animation_speed: Some("normal".to_string()),
-        start_with_system: Some(false),
-        start_minimized: Some(false),
-        auto_hide_after_launch: Some(false),
-        toggle_hotkey: Some("Ctrl+Space".to_string()),
-        global_hotkey: Some(true),
-        middle_mouse_toggle: Some(false),
-        fuzzy_search: Some(true),
-        search_in_path: Some(false),
-        max_search_results: Some(20),
-        auto_backup: Some(true),
-        backup_interval: Some("weekly".to_string()),
-        last_backup_time: None,
-        next_backup_time: None,
-        // 界面状态记录默认值
-        active_tab: Some("about".to_string()),
-        last_selected_category: None,
-        window_position_x: None,
-        window_position_y: None,
-        last_search_query: None,
-        grid_view_enabled: Some(false),
-        sort_order: Some("manual".to_string()),
-        show_hidden_files: Some(false),
-    }
-}
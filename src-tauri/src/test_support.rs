@@ -0,0 +1,68 @@
+//! 测试辅助工具
+//!
+//! 只在测试中编译，提供多个模块的测试共用的工具。
+
+use std::ffi::OsStr;
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+/// 环境变量是进程级的，修改环境变量的测试需要依次执行
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+/// 设置环境变量（`None` 表示删除）后执行 `f`，结束后恢复原来的值
+pub fn with_env<T>(vars: &[(&str, Option<&OsStr>)], f: impl FnOnce() -> T) -> T {
+    let _guard = ENV_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    let previous: Vec<_> = vars
+        .iter()
+        .map(|(name, _)| (*name, std::env::var_os(name)))
+        .collect();
+    for (name, value) in vars {
+        match value {
+            Some(value) => std::env::set_var(name, value),
+            None => std::env::remove_var(name),
+        }
+    }
+    let result = f();
+    for (name, value) in previous {
+        match value {
+            Some(value) => std::env::set_var(name, value),
+            None => std::env::remove_var(name),
+        }
+    }
+    result
+}
+
+/// 测试用的临时目录，离开作用域时连同其中的内容一起删除
+pub struct TestDir(PathBuf);
+
+impl TestDir {
+    /// 在系统临时目录中创建一个空目录，`name` 需要在所有测试中唯一
+    pub fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("lora_test_{}_{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+}
+
+impl Deref for TestDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TestDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
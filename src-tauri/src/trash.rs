@@ -0,0 +1,394 @@
+//! 回收站模块
+//!
+//! 删除的项目和分组不会立即丢弃，而是连同图标、快捷键、所属分组和使用统计
+//! 一起移入应用数据中的回收站，超过保留天数后自动清除：
+//! - 列出回收站中的条目
+//! - 恢复条目（恢复项目时重新注册其全局快捷键）
+//! - 彻底删除指定条目或清空回收站
+
+use std::collections::BTreeMap;
+use tauri::{AppHandle, State};
+
+use crate::data::{
+    normalize_app_categories, rollback_shortcut_registration, validate_project_hotkey,
+};
+use crate::models::{
+    AppData, AppSettings, AppState, AppStorage, CategoryData, TrashEntry, TrashItem,
+};
+use crate::store::Store;
+
+/// 未设置保留天数时使用的默认值
+const DEFAULT_TRASH_RETENTION_DAYS: u32 = 30;
+
+/// 从设置中读取回收站保留天数
+pub fn trash_retention_days(store: &Store) -> u32 {
+    store
+        .settings()
+        .ok()
+        .and_then(|settings| settings.trash_retention_days)
+        .unwrap_or(DEFAULT_TRASH_RETENTION_DAYS)
+}
+
+/// 早于该时间删除的条目已经过期
+fn trash_cutoff(retention_days: u32) -> i64 {
+    chrono::Utc::now().timestamp() - i64::from(retention_days) * 24 * 60 * 60
+}
+
+/// 清除超过保留天数的条目，保留天数为 0 时不保留任何条目
+pub fn purge_expired_trash(storage: &mut AppStorage, retention_days: u32) {
+    let cutoff = trash_cutoff(retention_days);
+    storage.trash.retain(|entry| entry.deleted_at > cutoff);
+}
+
+/// 把被删除的内容移入回收站，并顺便清除过期条目
+pub fn move_to_trash(storage: &mut AppStorage, item: TrashItem, retention_days: u32) {
+    let now = chrono::Utc::now();
+    let item_id = match &item {
+        TrashItem::App { app } => format!("app-{}", app.id),
+        TrashItem::Category { category, .. } => format!("category-{}", category.id),
+    };
+
+    storage.trash.push(TrashEntry {
+        id: format!("{}-{}", item_id, now.timestamp_millis()),
+        deleted_at: now.timestamp(),
+        item,
+    });
+    purge_expired_trash(storage, retention_days);
+}
+
+fn take_trash_entry(storage: &mut AppStorage, entry_id: &str) -> Result<TrashEntry, String> {
+    let index = storage
+        .trash
+        .iter()
+        .position(|entry| entry.id == entry_id)
+        .ok_or("回收站中不存在该条目")?;
+    Ok(storage.trash.remove(index))
+}
+
+/// 把项目放回应用列表，返回快捷键是否因冲突被清除
+fn restore_app(
+    storage: &mut AppStorage,
+    settings: Option<&AppSettings>,
+    mut app: AppData,
+) -> Result<bool, String> {
+    if storage.apps.iter().any(|existing| existing.id == app.id) {
+        return Err(format!("已存在 id 相同的项目: {}", app.id));
+    }
+
+    // 删除期间被移除的分组不再保留引用
+    app.category_ids.retain(|category_id| {
+        category_id == "all"
+            || storage
+                .categories
+                .iter()
+                .any(|category| &category.id == category_id)
+    });
+    app.category.clear();
+    normalize_app_categories(&mut app);
+
+    let hotkey_cleared = validate_project_hotkey(storage, settings, &mut app).is_err();
+    if hotkey_cleared {
+        app.shortcut_hotkey = None;
+    }
+
+    storage.apps.push(app);
+    Ok(hotkey_cleared)
+}
+
+/// 把分组放回分组列表，并恢复删除时仍然存在的项目对它的归属和在其中的排序
+fn restore_category(
+    storage: &mut AppStorage,
+    category: CategoryData,
+    app_ids: &[i64],
+    app_orders: &BTreeMap<i64, i32>,
+) -> Result<(), String> {
+    if storage
+        .categories
+        .iter()
+        .any(|existing| existing.id == category.id)
+    {
+        return Err(format!("已存在 id 相同的分组: {}", category.id));
+    }
+
+    for app in storage
+        .apps
+        .iter_mut()
+        .filter(|app| app_ids.contains(&app.id))
+    {
+        if !app.category_ids.contains(&category.id) {
+            app.category_ids.push(category.id.clone());
+            // 只因该分组被删除而归入"全部应用"的项目，恢复后不再单独归属"全部应用"
+            if app.category_ids.len() == 2 && app.category_ids[0] == "all" {
+                app.category_ids.remove(0);
+            }
+            app.category.clear();
+            normalize_app_categories(app);
+        }
+        if let Some(order) = app_orders.get(&app.id) {
+            app.category_orders.insert(category.id.clone(), *order);
+        }
+    }
+
+    storage.categories.push(category);
+    Ok(())
+}
+
+/// 获取回收站中的条目，最近删除的排在前面
+#[tauri::command]
+pub fn list_trash(state: State<'_, AppState>) -> Result<Vec<TrashEntry>, String> {
+    let retention_days = trash_retention_days(&state.store);
+    let cutoff = trash_cutoff(retention_days);
+    let has_expired = state
+        .store
+        .with_storage(|storage| storage.trash.iter().any(|entry| entry.deleted_at <= cutoff))?;
+    if has_expired {
+        state.store.update_storage(|storage| {
+            purge_expired_trash(storage, retention_days);
+            Ok(())
+        })?;
+    }
+
+    let mut entries = state.store.with_storage(|storage| storage.trash.clone())?;
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.deleted_at));
+    Ok(entries)
+}
+
+/// 恢复回收站中的条目
+#[tauri::command]
+pub fn restore_trash_item(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    entry_id: String,
+) -> Result<String, String> {
+    let settings = state.store.settings().ok();
+    let ((is_app, hotkey_cleared), entry_id) =
+        state
+            .store
+            .update_library_revertible("恢复回收站条目", |storage| {
+                let entry = take_trash_entry(storage, &entry_id)?;
+                match entry.item {
+                    TrashItem::App { app } => {
+                        let hotkey_cleared = restore_app(storage, settings.as_ref(), app)?;
+                        Ok((true, hotkey_cleared))
+                    }
+                    TrashItem::Category {
+                        category,
+                        app_ids,
+                        app_orders,
+                    } => {
+                        restore_category(storage, category, &app_ids, &app_orders)?;
+                        Ok((false, false))
+                    }
+                }
+            })?;
+
+    if !is_app {
+        return Ok("分组已恢复".to_string());
+    }
+
+    if let Err(error) = crate::system_integration::refresh_global_shortcuts(&app) {
+        return Err(rollback_shortcut_registration(
+            &app,
+            &state.store,
+            entry_id,
+            error,
+        ));
+    }
+
+    if hotkey_cleared {
+        Ok("项目已恢复，原快捷键已被占用，已清除".to_string())
+    } else {
+        Ok("项目已恢复".to_string())
+    }
+}
+
+/// 删除指定的回收站条目，未指定条目时清空回收站
+fn purge_trash_entries(storage: &mut AppStorage, entry_ids: Option<&[String]>) {
+    match entry_ids {
+        Some(entry_ids) => storage.trash.retain(|entry| !entry_ids.contains(&entry.id)),
+        None => storage.trash.clear(),
+    }
+}
+
+/// 彻底删除回收站中的条目，未指定条目时清空回收站
+///
+/// 彻底删除无法撤销，不记录到操作历史中。
+#[tauri::command]
+pub fn purge_trash(
+    state: State<'_, AppState>,
+    entry_ids: Option<Vec<String>>,
+) -> Result<String, String> {
+    state.store.update_storage(|storage| {
+        purge_trash_entries(storage, entry_ids.as_deref());
+        Ok(())
+    })?;
+    Ok("回收站已清理".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::STORAGE_SCHEMA_VERSION;
+
+    fn category(id: &str) -> CategoryData {
+        CategoryData {
+            id: id.to_string(),
+            name: id.to_string(),
+            icon: "icon-apps".to_string(),
+            is_default: id == "all",
+            order: None,
+            hidden: false,
+        }
+    }
+
+    fn app(id: i64, category_ids: &[&str]) -> AppData {
+        let mut app = AppData {
+            id,
+            name: format!("app{}", id),
+            category: String::new(),
+            category_ids: category_ids.iter().map(|id| id.to_string()).collect(),
+            icon: String::new(),
+            path: format!("/apps/{}", id),
+            target_path: None,
+            is_shortcut: false,
+            launch_args: None,
+            shortcut_hotkey: None,
+            target_type: None,
+            run_as_admin: false,
+            order: Some(id as i32),
+            category_orders: BTreeMap::new(),
+            usage_count: None,
+            last_launched_at: None,
+        };
+        normalize_app_categories(&mut app);
+        app
+    }
+
+    fn storage(apps: Vec<AppData>, categories: &[&str]) -> AppStorage {
+        AppStorage {
+            schema_version: STORAGE_SCHEMA_VERSION,
+            apps,
+            categories: categories.iter().map(|id| category(id)).collect(),
+            selected_category: None,
+            trash: Vec::new(),
+        }
+    }
+
+    fn find_app(storage: &AppStorage, id: i64) -> &AppData {
+        storage.apps.iter().find(|app| app.id == id).unwrap()
+    }
+
+    fn entry(id: &str, deleted_at: i64) -> TrashEntry {
+        TrashEntry {
+            id: id.to_string(),
+            deleted_at,
+            item: TrashItem::Category {
+                category: category(id),
+                app_ids: Vec::new(),
+                app_orders: BTreeMap::new(),
+            },
+        }
+    }
+
+    fn entry_ids(storage: &AppStorage) -> Vec<&str> {
+        storage
+            .trash
+            .iter()
+            .map(|entry| entry.id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn purge_removes_selected_entries_or_everything() {
+        let now = chrono::Utc::now().timestamp();
+        let mut storage = storage(Vec::new(), &["all"]);
+        storage.trash = vec![entry("a", now), entry("b", now), entry("c", now)];
+
+        purge_trash_entries(&mut storage, Some(&["b".to_string(), "x".to_string()]));
+        assert_eq!(entry_ids(&storage), ["a", "c"]);
+
+        purge_trash_entries(&mut storage, None);
+        assert!(storage.trash.is_empty());
+    }
+
+    #[test]
+    fn expired_entries_are_purged_after_retention_days() {
+        let now = chrono::Utc::now().timestamp();
+        let day = 24 * 60 * 60;
+        let mut storage = storage(Vec::new(), &["all"]);
+        storage.trash = vec![entry("old", now - 31 * day), entry("recent", now - day)];
+
+        purge_expired_trash(&mut storage, 30);
+        assert_eq!(entry_ids(&storage), ["recent"]);
+
+        purge_expired_trash(&mut storage, 0);
+        assert!(storage.trash.is_empty());
+    }
+
+    #[test]
+    fn moved_app_can_be_taken_back_and_restored() {
+        let mut storage = storage(vec![app(2, &["tools"])], &["all", "work", "tools"]);
+        let mut deleted = app(1, &["work", "tools"]);
+        deleted.usage_count = Some(7);
+        move_to_trash(&mut storage, TrashItem::App { app: deleted }, 30);
+        // 删除期间 work 分组也被删除了
+        storage.categories.retain(|category| category.id != "work");
+
+        let entry_id = storage.trash[0].id.clone();
+        let entry = take_trash_entry(&mut storage, &entry_id).unwrap();
+        assert!(storage.trash.is_empty());
+        assert!(take_trash_entry(&mut storage, &entry_id).is_err());
+
+        let TrashItem::App { app: restored } = entry.item else {
+            panic!("expected an app entry");
+        };
+        assert!(!restore_app(&mut storage, None, restored).unwrap());
+        let app1 = find_app(&storage, 1);
+        assert_eq!(app1.category_ids, ["tools"]);
+        assert_eq!(app1.usage_count, Some(7));
+
+        assert!(restore_app(&mut storage, None, app(1, &["all"])).is_err());
+    }
+
+    #[test]
+    fn restore_app_clears_conflicting_hotkey() {
+        let mut existing = app(2, &["all"]);
+        existing.shortcut_hotkey = Some("ctrl+alt+k".to_string());
+        let mut storage = storage(vec![existing], &["all"]);
+        let mut deleted = app(1, &["all"]);
+        deleted.shortcut_hotkey = Some("ctrl+alt+k".to_string());
+
+        assert!(restore_app(&mut storage, None, deleted).unwrap());
+        assert_eq!(find_app(&storage, 1).shortcut_hotkey, None);
+        assert_eq!(
+            find_app(&storage, 2).shortcut_hotkey.as_deref(),
+            Some("ctrl+alt+k")
+        );
+    }
+
+    #[test]
+    fn restore_category_restores_membership_and_order() {
+        // 删除 work 之后：项目 1 只属于 work，已归入"全部应用"；项目 2 还属于 tools
+        let mut storage = storage(
+            vec![app(1, &["all"]), app(2, &["tools"]), app(3, &["tools"])],
+            &["all", "tools"],
+        );
+        let app_orders = BTreeMap::from([(1, 5), (2, 3)]);
+
+        restore_category(&mut storage, category("work"), &[1, 2], &app_orders).unwrap();
+
+        assert!(storage
+            .categories
+            .iter()
+            .any(|category| category.id == "work"));
+        let app1 = find_app(&storage, 1);
+        assert_eq!(app1.category_ids, ["work"]);
+        assert_eq!(app1.category_orders.get("work"), Some(&5));
+        let app2 = find_app(&storage, 2);
+        assert_eq!(app2.category_ids, ["tools", "work"]);
+        assert_eq!(app2.category_orders.get("work"), Some(&3));
+        assert_eq!(find_app(&storage, 3).category_ids, ["tools"]);
+
+        assert!(restore_category(&mut storage, category("work"), &[], &BTreeMap::new()).is_err());
+    }
+}
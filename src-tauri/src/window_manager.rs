@@ -23,20 +23,17 @@ fn remember_settings_window_size(window: &tauri::WebviewWindow) {
         return;
     }
 
-    match crate::data::load_app_settings() {
-        Ok(mut settings) => {
-            settings.settings_window_width = Some(logical_size.width);
-            settings.settings_window_height = Some(logical_size.height);
-            if let Err(error) = crate::data::save_app_settings(settings) {
-                eprintln!("保存设置窗口大小失败: {}", error);
-            } else {
-                println!(
-                    "设置窗口大小已保存: {}x{}",
-                    logical_size.width, logical_size.height
-                );
-            }
-        }
-        Err(error) => eprintln!("加载设置以保存设置窗口大小失败: {}", error),
+    let store = &window.app_handle().state::<AppState>().store;
+    match store.update_settings(|settings| {
+        settings.settings_window_width = Some(logical_size.width);
+        settings.settings_window_height = Some(logical_size.height);
+        Ok(())
+    }) {
+        Ok(()) => println!(
+            "设置窗口大小已保存: {}x{}",
+            logical_size.width, logical_size.height
+        ),
+        Err(error) => eprintln!("保存设置窗口大小失败: {}", error),
     }
 }
 
@@ -51,20 +48,17 @@ fn remember_new_project_window_size(window: &tauri::WebviewWindow) {
         return;
     }
 
-    match crate::data::load_app_settings() {
-        Ok(mut settings) => {
-            settings.new_project_window_width = Some(logical_size.width);
-            settings.new_project_window_height = Some(logical_size.height);
-            if let Err(error) = crate::data::save_app_settings(settings) {
-                eprintln!("保存新建项目窗口大小失败: {}", error);
-            } else {
-                println!(
-                    "新建项目窗口大小已保存: {}x{}",
-                    logical_size.width, logical_size.height
-                );
-            }
-        }
-        Err(error) => eprintln!("加载设置以保存新建项目窗口大小失败: {}", error),
+    let store = &window.app_handle().state::<AppState>().store;
+    match store.update_settings(|settings| {
+        settings.new_project_window_width = Some(logical_size.width);
+        settings.new_project_window_height = Some(logical_size.height);
+        Ok(())
+    }) {
+        Ok(()) => println!(
+            "新建项目窗口大小已保存: {}x{}",
+            logical_size.width, logical_size.height
+        ),
+        Err(error) => eprintln!("保存新建项目窗口大小失败: {}", error),
     }
 }
 
@@ -203,7 +197,9 @@ pub async fn open_settings_window(
         }
     }
 
-    let (settings_width, settings_height) = crate::data::load_app_settings()
+    let (settings_width, settings_height) = state
+        .store
+        .settings()
         .ok()
         .map(|settings| {
             (
@@ -356,7 +352,9 @@ pub async fn open_new_project_window(
     // 克隆状态以便在闭包中使用
     let state_clone = state.new_project_window_open.clone();
 
-    let (new_project_width, new_project_height) = crate::data::load_app_settings()
+    let (new_project_width, new_project_height) = state
+        .store
+        .settings()
         .map(|settings| {
             (
                 settings.new_project_window_width.unwrap_or(600) as f64,
@@ -122,7 +122,7 @@ export default {
     confirm: {
       deleteApp: 'Are you sure you want to delete application "{name}"?',
       deleteAllApps: 'Are you sure you want to delete all applications in the current category?',
-      deleteCategory: 'Are you sure you want to delete category "{name}"? This will also delete {count} applications in this category.',
+      deleteCategory: 'Are you sure you want to delete category "{name}"? The {count} applications in this category will stay in their other categories or in "All Apps".',
       deleteCategoryEmpty: 'Are you sure you want to delete category "{name}"?',
       deleteAllCategories: 'Are you sure you want to delete all {groupCount} custom categories? This will also delete {appCount} applications.',
       deleteAllCategoriesEmpty: 'Are you sure you want to delete all {groupCount} custom categories?',
@@ -136,7 +136,8 @@ export default {
       openFileLocationFailed: 'Failed to open file location: {error}',
       browseFileFailed: 'Failed to browse file: {error}',
       selectIconFailed: 'Failed to select icon: {error}',
-      noCustomCategories: 'No custom categories available to delete.'
+      noCustomCategories: 'No custom categories available to delete.',
+      deleteCategoryFailed: 'Failed to delete category: {error}'
     }
   },
 
@@ -252,9 +253,19 @@ export default {
         autoBackup: 'Auto Backup Data',
         autoBackupDesc: 'Periodically automatically backup application data to prevent data loss',
         backupInterval: 'Backup Frequency',
+        hourly: 'Hourly',
         daily: 'Daily',
         weekly: 'Weekly',
         monthly: 'Monthly',
+        customInterval: 'Custom',
+        customIntervalPlaceholder: 'e.g. every 6h or 0 3 * * *',
+        customIntervalDesc: 'Accepts fixed intervals (every 6h, 90m, 1d 12h with units m, h, d, w) and cron expressions (minute hour day month weekday, use L in the day field for the last day of the month)',
+        backupStatus: 'Backup Status',
+        backupNever: 'No backup has been made yet',
+        backupRunning: 'Backing up...',
+        backupOk: 'Last backup succeeded at {time}',
+        backupFailing: 'The last {count} backup(s) failed',
+        backupLastError: 'Last failure at {time}: {error}',
         exportData: 'Export Data',
         importData: 'Import Data',
         resetData: 'Reset Data'
@@ -279,7 +290,8 @@ export default {
       resetFailed: 'Failed to reset data: {error}',
       restoreDefaultsSuccess: 'Settings restored to default values',
       restoreDefaultsFailed: 'Failed to restore default settings: {error}',
-      updateCategoryVisibilityFailed: 'Failed to update category display state: {error}'
+      updateCategoryVisibilityFailed: 'Failed to update category display state: {error}',
+      updateBackupIntervalFailed: 'Failed to update backup frequency: {error}'
     }
   },
 
@@ -450,7 +462,8 @@ export default {
       loadFailed: 'Loading Failed',
       loadAppDataFailed: 'Failed to load application data',
       noAppId: 'Application ID parameter not found, please ensure the edit window is opened correctly',
-      getAppFailed: 'Failed to get application data'
+      getAppFailed: 'Failed to get application data',
+      projectRemoved: 'This project has been deleted'
     },
     confirm: {
       fillRequired: 'Please fill in the required information'
@@ -122,7 +122,7 @@ export default {
     confirm: {
       deleteApp: '确定要删除应用 "{name}" 吗？',
       deleteAllApps: '确定要删除当前分类下的所有应用吗？',
-      deleteCategory: '确定要删除分组 "{name}" 吗？这将同时删除该分组下的 {count} 个应用。',
+      deleteCategory: '确定要删除分组 "{name}" 吗？该分组下的 {count} 个应用会保留在其他分组或"全部应用"中。',
       deleteCategoryEmpty: '确定要删除分组 "{name}" 吗？',
       deleteAllCategories: '确定要删除所有 {groupCount} 个自定义分组吗？这将同时删除 {appCount} 个应用。',
       deleteAllCategoriesEmpty: '确定要删除所有 {groupCount} 个自定义分组吗？',
@@ -136,7 +136,8 @@ export default {
       openFileLocationFailed: '打开文件位置失败: {error}',
       browseFileFailed: '浏览文件失败: {error}',
       selectIconFailed: '选择图标失败: {error}',
-      noCustomCategories: '没有自定义分组可以删除。'
+      noCustomCategories: '没有自定义分组可以删除。',
+      deleteCategoryFailed: '删除分组失败: {error}'
     }
   },
 
@@ -252,9 +253,19 @@ export default {
         autoBackup: '自动备份数据',
         autoBackupDesc: '定期自动备份应用数据，防止数据丢失',
         backupInterval: '备份频率',
+        hourly: '每小时',
         daily: '每天',
         weekly: '每周',
         monthly: '每月',
+        customInterval: '自定义',
+        customIntervalPlaceholder: '例如 every 6h 或 0 3 * * *',
+        customIntervalDesc: '支持固定间隔（every 6h、90m、1d 12h，单位 m、h、d、w）和 cron 表达式（分 时 日 月 周，日字段可用 L 表示月末）',
+        backupStatus: '备份状态',
+        backupNever: '还没有进行过备份',
+        backupRunning: '正在备份...',
+        backupOk: '最近一次备份成功于 {time}',
+        backupFailing: '最近 {count} 次备份失败',
+        backupLastError: '最近一次失败于 {time}: {error}',
         exportData: '导出数据',
         importData: '导入数据',
         resetData: '重置数据'
@@ -279,7 +290,8 @@ export default {
       resetFailed: '重置数据失败: {error}',
       restoreDefaultsSuccess: '设置已恢复到默认值',
       restoreDefaultsFailed: '恢复默认设置失败: {error}',
-      updateCategoryVisibilityFailed: '更新分组显示状态失败: {error}'
+      updateCategoryVisibilityFailed: '更新分组显示状态失败: {error}',
+      updateBackupIntervalFailed: '更新备份频率失败: {error}'
     }
   },
 
@@ -450,7 +462,8 @@ export default {
       loadFailed: '加载失败',
       loadAppDataFailed: '加载应用数据失败',
       noAppId: '未找到应用ID参数，请确保通过正确的方式打开编辑窗口',
-      getAppFailed: '获取应用数据失败'
+      getAppFailed: '获取应用数据失败',
+      projectRemoved: '该项目已被删除'
     },
     confirm: {
       fillRequired: '请填写必要的信息'